@@ -0,0 +1,91 @@
+//! 对比列表查询的 SQL 构建耗时：运行时 `format!` 拼接 与 生成期预计算的静态片段
+//!
+//! 运行：`cargo bench --bench list-filter`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use db_derive::Db;
+
+#[derive(Db, Default, Debug, sqlx::FromRow)]
+#[db(table = "messages", pk = "id")]
+pub struct Message {
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+
+    #[db(list_opt)]
+    #[db(skip_update)]
+    pub user_id: String,
+
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub content: String,
+
+    pub reply_to: Option<i32>,
+}
+
+const SQL: &str = r#"SELECT "id", "user_id", "content", "reply_to" FROM "messages" WHERE 1=1"#;
+const ITERS: u32 = 200_000;
+
+/// 旧版生成代码的等价写法
+fn push_list_filter_format<'q>(
+    q: &mut sqlx::QueryBuilder<'q, sqlx::Postgres>,
+    f: &'q MessageListFilter,
+) {
+    if let Some(v) = &f.user_id {
+        if false {
+            let param = format!("%{}%", v);
+            q.push(format!(" AND {} ILIKE ", &"\"user_id\""))
+                .push_bind(param);
+        } else {
+            q.push(format!(" AND {} = ", &"\"user_id\"")).push_bind(v);
+        }
+    }
+    if let Some(v) = &f.content {
+        if true {
+            let param = format!("%{}%", v);
+            q.push(format!(" AND {} ILIKE ", &"\"content\""))
+                .push_bind(param);
+        } else {
+            q.push(format!(" AND {} = ", &"\"content\"")).push_bind(v);
+        }
+    }
+}
+
+type PushFn = for<'q> fn(&mut sqlx::QueryBuilder<'q, sqlx::Postgres>, &'q MessageListFilter);
+
+fn run(name: &str, f: &MessageListFilter, push: PushFn) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERS {
+        let mut q = sqlx::QueryBuilder::new(SQL);
+        push(&mut q, black_box(f));
+        black_box(q.sql());
+    }
+    let cost = start.elapsed();
+    println!(
+        "{:<8} {:>10.2?} 总计, {:>8.1?} / 次",
+        name,
+        cost,
+        cost / ITERS
+    );
+    cost
+}
+
+fn main() {
+    let f = MessageListFilter {
+        pq: MessagePaginateReq::new(0),
+        user_id: Some("AXUM-RS".into()),
+        content: Some("axum".into()),
+        cond: None,
+    };
+
+    // 预热
+    run("warmup", &f, push_list_filter_format);
+
+    let before = run("format", &f, push_list_filter_format);
+    let after = run("static", &f, Message::push_list_filter);
+    println!(
+        "提升 {:.1}%",
+        (1.0 - after.as_secs_f64() / before.as_secs_f64()) * 100.0
+    );
+}
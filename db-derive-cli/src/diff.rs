@@ -0,0 +1,283 @@
+use db_derive_runtime::{ColumnMeta, TableMeta};
+
+use crate::schema::LiveSchema;
+
+/// 数据库类型，决定类型的比较方式及迁移语句的写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    Sqlite,
+}
+
+impl Backend {
+    /// 根据连接地址判断，`sqlite:` 开头为 SQLite，其余为 PostgreSQL
+    pub fn from_url(url: &str) -> Self {
+        if url.starts_with("sqlite:") {
+            Self::Sqlite
+        } else {
+            Self::Postgres
+        }
+    }
+}
+
+/// 结构体元数据与数据库表结构之间的差异
+#[derive(Debug)]
+pub enum Change {
+    /// 表不存在
+    MissingTable(&'static TableMeta),
+    /// 列不存在
+    MissingColumn(&'static TableMeta, &'static ColumnMeta),
+    /// 列类型不一致
+    TypeMismatch {
+        table: &'static TableMeta,
+        column: &'static ColumnMeta,
+        actual: String,
+    },
+    /// 列是否可空不一致
+    NullableMismatch(&'static TableMeta, &'static ColumnMeta),
+    /// 数据库中多出的列，仅提示，不生成迁移语句
+    ExtraColumn { table: &'static str, column: String },
+}
+
+impl Change {
+    /// 对应的迁移语句
+    ///
+    /// SQLite 不支持修改列的类型及是否可空，此时不生成语句，需手动重建表
+    pub fn to_sql(&self, backend: Backend) -> Vec<String> {
+        match self {
+            Self::MissingTable(t) => match backend {
+                Backend::Postgres => {
+                    let mut ss = vec![t.create_sql.to_string()];
+                    ss.extend(t.index_sql.iter().map(|s| s.to_string()));
+                    ss
+                }
+                Backend::Sqlite => {
+                    let mut ss = vec![sqlite_create_sql(t)];
+                    // GIN 索引及扩展仅适用于 PostgreSQL
+                    ss.extend(
+                        t.index_sql
+                            .iter()
+                            .filter(|s| {
+                                !s.contains(" USING GIN ") && !s.starts_with("CREATE EXTENSION")
+                            })
+                            .map(|s| s.to_string()),
+                    );
+                    ss
+                }
+            },
+            Self::MissingColumn(t, c) => {
+                let mut ss = format!(
+                    "ALTER TABLE {:?} ADD COLUMN {:?} {}",
+                    t.table, c.name, c.sql_type
+                );
+                if !c.nullable {
+                    ss.push_str(" NOT NULL");
+                }
+                if c.unique && backend == Backend::Postgres {
+                    ss.push_str(" UNIQUE");
+                }
+                if let Some(v) = c.default {
+                    ss.push_str(" DEFAULT ");
+                    ss.push_str(v);
+                }
+                let mut ss = vec![ss];
+                // SQLite 新增的列不能带 UNIQUE 约束，改为唯一索引
+                if c.unique && backend == Backend::Sqlite {
+                    ss.push(format!(
+                        "CREATE UNIQUE INDEX IF NOT EXISTS {:?} ON {:?} ({:?})",
+                        format!("uniq_{}_{}", t.table, c.name),
+                        t.table,
+                        c.name
+                    ));
+                }
+                ss
+            }
+            Self::TypeMismatch { .. } | Self::NullableMismatch(..)
+                if backend == Backend::Sqlite =>
+            {
+                vec![]
+            }
+            Self::TypeMismatch { table, column, .. } => {
+                let ty = alter_type(column.sql_type);
+                vec![format!(
+                    "ALTER TABLE {:?} ALTER COLUMN {:?} TYPE {} USING {:?}::{}",
+                    table.table, column.name, ty, column.name, ty
+                )]
+            }
+            Self::NullableMismatch(t, c) => {
+                let action = if c.nullable {
+                    "DROP NOT NULL"
+                } else {
+                    "SET NOT NULL"
+                };
+                vec![format!(
+                    "ALTER TABLE {:?} ALTER COLUMN {:?} {}",
+                    t.table, c.name, action
+                )]
+            }
+            Self::ExtraColumn { .. } => vec![],
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingTable(t) => write!(f, "缺少数据表 {}", t.table),
+            Self::MissingColumn(t, c) => write!(f, "{}: 缺少列 {}", t.table, c.name),
+            Self::TypeMismatch {
+                table,
+                column,
+                actual,
+            } => write!(
+                f,
+                "{}.{}: 类型不一致，期望 {}，实际 {}",
+                table.table, column.name, column.sql_type, actual
+            ),
+            Self::NullableMismatch(t, c) => write!(
+                f,
+                "{}.{}: 期望{}",
+                t.table,
+                c.name,
+                if c.nullable { "可空" } else { "非空" }
+            ),
+            Self::ExtraColumn { table, column } => {
+                write!(f, "{}.{}: 数据库中存在但结构体中没有的列", table, column)
+            }
+        }
+    }
+}
+
+/// 对比结构体元数据和数据库表结构
+pub fn diff(tables: &[&'static TableMeta], live: &LiveSchema, backend: Backend) -> Vec<Change> {
+    let mut changes = vec![];
+
+    for t in tables {
+        let Some(live_columns) = live.get(t.table).filter(|c| !c.is_empty()) else {
+            changes.push(Change::MissingTable(t));
+            continue;
+        };
+        for c in t.columns {
+            let Some(lc) = live_columns.iter().find(|lc| lc.name == c.name) else {
+                changes.push(Change::MissingColumn(t, c));
+                continue;
+            };
+            if canonical_type(c.sql_type, backend) != canonical_type(&lc.data_type, backend) {
+                changes.push(Change::TypeMismatch {
+                    table: t,
+                    column: c,
+                    actual: lc.data_type.clone(),
+                });
+            }
+            // 主键隐含非空，部分数据库不会在元数据中体现
+            if c.name != t.pk && c.nullable != lc.nullable {
+                changes.push(Change::NullableMismatch(t, c));
+            }
+        }
+        for lc in live_columns {
+            if t.column(&lc.name).is_none() {
+                changes.push(Change::ExtraColumn {
+                    table: t.table,
+                    column: lc.name.clone(),
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+/// 规范化的类型名称
+///
+/// PostgreSQL 与 `information_schema.columns.data_type` 一致；SQLite 为类型亲和性，如 `VARCHAR` 与 `TEXT` 均为 `text`
+pub fn canonical_type(ty: &str, backend: Backend) -> String {
+    if backend == Backend::Sqlite {
+        return sqlite_affinity(ty).to_string();
+    }
+    let ty = ty.trim().to_lowercase();
+    if ty.ends_with("[]") {
+        return "ARRAY".to_string();
+    }
+    // 忽略长度、精度等参数
+    let base = match ty.find('(') {
+        Some(i) => {
+            let rest = ty[ty.find(')').map(|j| j + 1).unwrap_or(ty.len())..].trim();
+            format!("{} {}", ty[..i].trim(), rest).trim().to_string()
+        }
+        None => ty,
+    };
+    let t = match base.as_str() {
+        "serial" | "serial4" | "int" | "int4" | "integer" => "integer",
+        "bigserial" | "serial8" | "int8" | "bigint" => "bigint",
+        "smallserial" | "serial2" | "int2" | "smallint" => "smallint",
+        "varchar" | "character varying" => "character varying",
+        "char" | "character" | "bpchar" => "character",
+        "bool" | "boolean" => "boolean",
+        "float4" | "real" => "real",
+        "float8" | "double precision" => "double precision",
+        "decimal" | "numeric" => "numeric",
+        "timestamptz" | "timestamp with time zone" => "timestamp with time zone",
+        "timestamp" | "timestamp without time zone" => "timestamp without time zone",
+        "timetz" | "time with time zone" => "time with time zone",
+        "time" | "time without time zone" => "time without time zone",
+        _ => return base,
+    };
+    t.to_string()
+}
+
+/// SQLite 按声明的类型名称确定的类型亲和性
+fn sqlite_affinity(ty: &str) -> &'static str {
+    let ty = alter_type(ty).to_uppercase();
+    if ty.contains("INT") {
+        "integer"
+    } else if ty.contains("CHAR") || ty.contains("CLOB") || ty.contains("TEXT") {
+        "text"
+    } else if ty.contains("BLOB") || ty.is_empty() {
+        "blob"
+    } else if ty.contains("REAL") || ty.contains("FLOA") || ty.contains("DOUB") {
+        "real"
+    } else {
+        "numeric"
+    }
+}
+
+/// SQLite 的建表语句：自增类型改为 `INTEGER`，以便整数主键由数据库生成
+fn sqlite_create_sql(t: &TableMeta) -> String {
+    let columns = t
+        .columns
+        .iter()
+        .map(|c| {
+            let ty = if c.sql_type.to_uppercase().ends_with("SERIAL") {
+                "INTEGER"
+            } else {
+                c.sql_type
+            };
+            let mut ss = format!("  {:?} {}", c.name, ty);
+            if c.name == t.pk {
+                ss.push_str(" PRIMARY KEY");
+            } else if !c.nullable {
+                ss.push_str(" NOT NULL");
+            }
+            if c.unique {
+                ss.push_str(" UNIQUE");
+            }
+            if let Some(v) = c.default {
+                ss.push_str(" DEFAULT ");
+                ss.push_str(v);
+            }
+            ss
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("CREATE TABLE IF NOT EXISTS {:?} (\n{}\n)", t.table, columns)
+}
+
+/// `ALTER COLUMN ... TYPE` 中使用的类型，自增类型不能用于修改列类型
+fn alter_type(ty: &str) -> &str {
+    match ty.to_uppercase().as_str() {
+        "SERIAL" => "INTEGER",
+        "BIGSERIAL" => "BIGINT",
+        "SMALLSERIAL" => "SMALLINT",
+        _ => ty,
+    }
+}
@@ -0,0 +1,150 @@
+//! 对比 `#[derive(Db)]` 结构体与数据库表结构，并生成迁移语句。
+//!
+//! 在项目中新建 `src/bin/db-derive-cli.rs`，注册需要对比的实体（需声明 `#[db(ddl)]`）：
+//!
+//! ```ignore
+//! fn main() -> std::process::ExitCode {
+//!     db_derive_cli::Registry::new()
+//!         .register::<Message>()
+//!         .register::<User>()
+//!         .run()
+//! }
+//! ```
+//!
+//! 然后执行：
+//!
+//! ```text
+//! cargo run --bin db-derive-cli -- diff --database-url postgres://... --out migrations/0002_diff.sql
+//! ```
+
+pub mod diff;
+pub mod schema;
+
+use std::process::ExitCode;
+
+use db_derive_runtime::{Table, TableMeta};
+
+pub use diff::{diff, Backend, Change};
+pub use schema::{LiveColumn, LiveSchema};
+
+const USAGE: &str = "用法: db-derive-cli diff [--database-url <URL>] [--out <FILE>]
+
+  --database-url  数据库连接地址，默认读取环境变量 DATABASE_URL
+  --out           迁移文件路径，默认输出到标准输出";
+
+/// 参与对比的实体，通过 `register` 逐个注册
+#[derive(Debug, Default)]
+pub struct Registry {
+    tables: Vec<&'static TableMeta>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册实体，需为 `#[derive(Db)]` 且能生成建表语句
+    pub fn register<T: Table>(mut self) -> Self {
+        self.tables.push(T::table_meta());
+        self
+    }
+
+    /// 已注册实体的元数据
+    pub fn tables(&self) -> &[&'static TableMeta] {
+        &self.tables
+    }
+
+    /// 以已注册的实体作为命令行入口
+    pub fn run(&self) -> ExitCode {
+        run(&self.tables)
+    }
+}
+
+/// 生成迁移文件内容
+pub fn migration(changes: &[Change], backend: Backend) -> String {
+    let mut ss = String::from("-- 由 db-derive-cli 生成\n");
+    for c in changes {
+        ss.push_str(&format!("\n-- {}\n", c));
+        let sqls = c.to_sql(backend);
+        if sqls.is_empty() && !matches!(c, Change::ExtraColumn { .. }) {
+            ss.push_str("-- SQLite 不支持修改列定义，请手动重建该表\n");
+        }
+        for sql in sqls {
+            ss.push_str(&sql);
+            ss.push_str(";\n");
+        }
+    }
+    ss
+}
+
+/// 读取数据库表结构
+pub async fn load(database_url: &str, tables: &[&'static TableMeta]) -> sqlx::Result<LiveSchema> {
+    let names = tables.iter().map(|t| t.table).collect::<Vec<_>>();
+
+    #[cfg(feature = "sqlite")]
+    if Backend::from_url(database_url) == Backend::Sqlite {
+        let pool = sqlx::SqlitePool::connect(database_url).await?;
+        return schema::load_sqlite(&pool, &names).await;
+    }
+
+    let pool = sqlx::PgPool::connect(database_url).await?;
+    schema::load_pg(&pool, &names).await
+}
+
+/// 命令行入口
+pub fn run(tables: &[&'static TableMeta]) -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("diff") {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let mut database_url = std::env::var("DATABASE_URL").ok();
+    let mut out = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--database-url" => database_url = args.next(),
+            "--out" => out = args.next(),
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(database_url) = database_url else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let live = match rt.block_on(load(&database_url, tables)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("读取数据库表结构失败: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let backend = Backend::from_url(&database_url);
+    let changes = diff(tables, &live, backend);
+    if changes.is_empty() {
+        eprintln!("数据库表结构与结构体一致");
+        return ExitCode::SUCCESS;
+    }
+    for c in changes.iter() {
+        eprintln!("{}", c);
+    }
+
+    let sql = migration(&changes, backend);
+    match out {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, sql) {
+                eprintln!("写入迁移文件失败: {}", e);
+                return ExitCode::FAILURE;
+            }
+            eprintln!("迁移文件已写入 {}", path);
+        }
+        None => println!("{}", sql),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+/// 数据库中实际存在的列
+#[derive(Debug, Clone)]
+pub struct LiveColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// 数据库中实际存在的表结构，表名 => 列
+pub type LiveSchema = HashMap<String, Vec<LiveColumn>>;
+
+/// 从 PostgreSQL 的 `information_schema` 读取表结构
+pub async fn load_pg(pool: &sqlx::PgPool, tables: &[&str]) -> sqlx::Result<LiveSchema> {
+    let tables = tables.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    let rows: Vec<(String, String, String, bool)> = sqlx::query_as(
+        r#"SELECT table_name::text, column_name::text, data_type::text, is_nullable = 'YES'
+        FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = ANY($1)
+        ORDER BY table_name, ordinal_position"#,
+    )
+    .bind(&tables)
+    .fetch_all(pool)
+    .await?;
+
+    let mut schema = LiveSchema::new();
+    for (table, name, data_type, nullable) in rows {
+        schema.entry(table).or_default().push(LiveColumn {
+            name,
+            data_type,
+            nullable,
+        });
+    }
+    Ok(schema)
+}
+
+/// 从 SQLite 的 `pragma_table_info` 读取表结构，便于在本地测试
+#[cfg(feature = "sqlite")]
+pub async fn load_sqlite(pool: &sqlx::SqlitePool, tables: &[&str]) -> sqlx::Result<LiveSchema> {
+    let mut schema = LiveSchema::new();
+    for table in tables {
+        let rows: Vec<(String, String, bool)> =
+            sqlx::query_as(r#"SELECT name, type, "notnull" FROM pragma_table_info(?)"#)
+                .bind(table)
+                .fetch_all(pool)
+                .await?;
+        if rows.is_empty() {
+            continue;
+        }
+        let columns = rows
+            .into_iter()
+            .map(|(name, data_type, notnull)| LiveColumn {
+                name,
+                data_type,
+                nullable: !notnull,
+            })
+            .collect();
+        schema.insert(table.to_string(), columns);
+    }
+    Ok(schema)
+}
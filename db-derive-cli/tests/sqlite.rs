@@ -0,0 +1,86 @@
+use db_derive::Db;
+use db_derive_cli::{Backend, Change, Registry};
+
+#[derive(Db, Default, Debug, sqlx::FromRow)]
+#[db(table = "messages", pk = "id", ddl)]
+pub struct Message {
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+
+    #[db(list_opt)]
+    #[db(skip_update)]
+    pub user_id: String,
+
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub content: String,
+
+    pub title: Option<String>,
+
+    #[db(unique)]
+    pub slug: Option<String>,
+
+    #[db(default = "0")]
+    pub views: i32,
+}
+
+#[derive(Db, Default, Debug, sqlx::FromRow)]
+#[db(table = "tags", ddl)]
+pub struct Tag {
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub name: String,
+}
+
+#[tokio::test]
+async fn diff_and_migrate() {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query(
+        r#"CREATE TABLE messages (id INTEGER PRIMARY KEY, user_id TEXT, content VARCHAR NOT NULL, legacy INTEGER)"#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let registry = Registry::new().register::<Message>().register::<Tag>();
+    let tables = registry.tables();
+    let names = tables.iter().map(|t| t.table).collect::<Vec<_>>();
+    let live = db_derive_cli::schema::load_sqlite(&pool, &names)
+        .await
+        .unwrap();
+
+    // VARCHAR 与 TEXT 的类型亲和性相同，不视为类型不一致
+    let changes = db_derive_cli::diff(tables, &live, Backend::Sqlite);
+    let lines = changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "messages.user_id: 期望非空",
+            "messages: 缺少列 title",
+            "messages: 缺少列 slug",
+            "messages: 缺少列 views",
+            "messages.legacy: 数据库中存在但结构体中没有的列",
+            "缺少数据表 tags",
+        ]
+    );
+    assert!(changes[0].to_sql(Backend::Sqlite).is_empty());
+
+    // 生成的迁移语句可在 SQLite 上执行
+    for c in changes.iter() {
+        for sql in c.to_sql(Backend::Sqlite) {
+            sqlx::query(&sql).execute(&pool).await.unwrap();
+        }
+    }
+
+    let live = db_derive_cli::schema::load_sqlite(&pool, &names)
+        .await
+        .unwrap();
+    let changes = db_derive_cli::diff(tables, &live, Backend::Sqlite);
+    assert_eq!(changes.len(), 2);
+    assert!(matches!(changes[0], Change::NullableMismatch(_, c) if c.name == "user_id"));
+    assert!(matches!(&changes[1], Change::ExtraColumn { column, .. } if column == "legacy"));
+}
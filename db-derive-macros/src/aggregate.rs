@@ -0,0 +1,133 @@
+use quote::{format_ident, quote};
+use syn::{Ident, Type};
+
+use crate::{
+    db::{list_filter_push_ts, DbMeta},
+    ddl::{_generic_inner, _type_name},
+};
+
+/// 为 `#[db(aggregate)]` 字段生成 `sum_`、`avg_`、`min_`、`max_`、`count_distinct_` 聚合方法
+pub(crate) fn aggregate_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let filter_ident = Ident::new(&format!("{}ListFilter", dm.ident), dm.ident.span());
+
+    let methods = dm.fields.iter().filter(|f| f.aggregate).map(|f| {
+        let name = &f.name;
+        let col = format!("{:?}", name.to_string());
+        let ty = _generic_inner(&f.ty, "Option").unwrap_or(&f.ty);
+        let ty_name = _type_name(ty).unwrap_or_default();
+
+        // 整数求和、平均值统一转换为 i64 / f64，其它类型（如 Decimal）沿用字段类型
+        let (sum_cast, sum_ty, avg_cast, avg_ty): (_, Type, _, Type) = match ty_name.as_str() {
+            "i8" | "i16" | "i32" | "i64" => (
+                "::BIGINT",
+                syn::parse_quote!(i64),
+                "::DOUBLE PRECISION",
+                syn::parse_quote!(f64),
+            ),
+            "f32" | "f64" => (
+                "::DOUBLE PRECISION",
+                syn::parse_quote!(f64),
+                "::DOUBLE PRECISION",
+                syn::parse_quote!(f64),
+            ),
+            "String" | "str" | "bool" => panic!("字段 {} 不是数值类型，不能使用 aggregate", name),
+            _ => ("", ty.clone(), "", ty.clone()),
+        };
+
+        let sql = |expr: String| format!("SELECT {} FROM {:?} WHERE 1=1", expr, &dm.table);
+        let sum_sql = sql(format!("SUM({}){}", &col, sum_cast));
+        let avg_sql = sql(format!("AVG({}){}", &col, avg_cast));
+        let min_sql = sql(format!("MIN({})", &col));
+        let max_sql = sql(format!("MAX({})", &col));
+        let count_distinct_sql = sql(format!("COUNT(DISTINCT {})", &col));
+
+        let sum_fn = format_ident!("sum_{}", name);
+        let avg_fn = format_ident!("avg_{}", name);
+        let min_fn = format_ident!("min_{}", name);
+        let max_fn = format_ident!("max_{}", name);
+        let count_distinct_fn = format_ident!("count_distinct_{}", name);
+
+        let fetch_one = dm.tail_ts(quote! { q.build_query_scalar().fetch_one(e).await });
+        let method = |doc: String, func: &Ident, sql: &str, ok: proc_macro2::TokenStream| {
+            let ret = dm.result_ts(ok);
+            quote! {
+                #[doc = #doc]
+                pub async fn #func<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &#filter_ident) -> #ret {
+                    let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+                    Self::push_list_filter(&mut q, f);
+                    #fetch_one
+                }
+            }
+        };
+
+        let option = quote! { ::std::option::Option };
+        [
+            method(format!("符合条件的记录中 `{}` 的总和", name), &sum_fn, &sum_sql, quote! { #option<#sum_ty> }),
+            method(format!("符合条件的记录中 `{}` 的平均值", name), &avg_fn, &avg_sql, quote! { #option<#avg_ty> }),
+            method(format!("符合条件的记录中 `{}` 的最小值", name), &min_fn, &min_sql, quote! { #option<#ty> }),
+            method(format!("符合条件的记录中 `{}` 的最大值", name), &max_fn, &max_sql, quote! { #option<#ty> }),
+            method(format!("符合条件的记录中 `{}` 不同取值的个数", name), &count_distinct_fn, &count_distinct_sql, quote! { i64 }),
+        ]
+        .into_iter()
+        .collect::<proc_macro2::TokenStream>()
+    });
+
+    quote! {
+        #( #methods )*
+    }
+}
+
+/// 为 `#[db(facet)]` 字段生成分组计数 `facets_` 及去重取值 `distinct_` 方法
+///
+/// 两者都应用列表过滤条件，但不包括该字段自身的过滤条件
+pub(crate) fn facet_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let filter_ident = Ident::new(&format!("{}ListFilter", dm.ident), dm.ident.span());
+
+    let methods = dm.fields.iter().filter(|f| f.facet).map(|f| {
+        let name = &f.name;
+        let ty = &f.ty;
+        let col = format!("{:?}", name.to_string());
+        let filter_push = list_filter_push_ts(dm, Some(name));
+
+        let facets_sql = format!("SELECT {}, COUNT(*) FROM {:?} WHERE 1=1", &col, &dm.table);
+        let facets_order = format!(" GROUP BY {0} ORDER BY COUNT(*) DESC, {0} LIMIT ", &col);
+        let distinct_sql = format!("SELECT DISTINCT {} FROM {:?} WHERE 1=1", &col, &dm.table);
+        let distinct_order = format!(" ORDER BY {}", &col);
+
+        let facets_fn = format_ident!("facets_{}", name);
+        let distinct_fn = format_ident!("distinct_{}", name);
+        let facets_doc = format!("按 `{}` 分组计数，按数量降序返回前 `limit` 组", name);
+        let distinct_doc = format!("`{}` 的全部不同取值", name);
+        let facets_ret = dm.result_ts(quote! { Vec<(#ty, i64)> });
+        let distinct_ret = dm.result_ts(quote! { Vec<#ty> });
+        let facets_fetch = dm.tail_ts(quote! { qb.build_query_as().fetch_all(e).await });
+        let distinct_fetch = dm.tail_ts(quote! { qb.build_query_scalar().fetch_all(e).await });
+
+        quote! {
+            #[doc = #facets_doc]
+            pub async fn #facets_fn<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &#filter_ident, limit: u32) -> #facets_ret {
+                let mut qb = #krate::sqlx::QueryBuilder::new(#facets_sql);
+                let q = &mut qb;
+                #filter_push
+                q.push(#facets_order).push_bind(i64::from(limit));
+
+                #facets_fetch
+            }
+            #[doc = #distinct_doc]
+            pub async fn #distinct_fn<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &#filter_ident) -> #distinct_ret {
+                let mut qb = #krate::sqlx::QueryBuilder::new(#distinct_sql);
+                let q = &mut qb;
+                #filter_push
+                q.push(#distinct_order);
+
+                #distinct_fetch
+            }
+        }
+    });
+
+    quote! {
+        #( #methods )*
+    }
+}
@@ -0,0 +1,1471 @@
+use quote::quote;
+use syn::{
+    parse_macro_input, punctuated::Punctuated, token::Comma, Attribute, DeriveInput, Field, Ident,
+    LitInt, LitStr, Path, Type,
+};
+
+use crate::{
+    hook::Hooks,
+    validate::{parse_rules, patch_validate_ts, Rule},
+};
+
+#[derive(Debug)]
+pub(crate) struct DbMeta {
+    pub(crate) ident: Ident,
+    pub(crate) table: String,
+    pub(crate) pk: String,
+    pub(crate) is_view: bool,
+    pub(crate) krate: Path,
+    pub(crate) max_page_size: Option<u32>,
+    pub(crate) filter_derive: Vec<Path>,
+    pub(crate) router: bool,
+    pub(crate) hooks: Hooks,
+    /// 查询方法返回 `{Name}Error` 而非 `sqlx::Error`
+    pub(crate) db_error: bool,
+    /// 为生成的类型派生 utoipa 的 `ToSchema` / `IntoParams`
+    pub(crate) utoipa: bool,
+    /// 生成建表语句、表元数据并实现 `Table`
+    pub(crate) ddl: bool,
+    pub(crate) fields: Vec<DbField>,
+}
+
+impl DbMeta {
+    pub(crate) fn insert_fileds(&self) -> Vec<Ident> {
+        self.fields
+            .iter()
+            .filter(|f| !f.skip_insert)
+            .map(|f| f.name.clone())
+            .collect()
+    }
+    pub(crate) fn update_fileds(&self) -> Vec<Ident> {
+        self.fields
+            .iter()
+            .filter(|f| !f.skip_update && !f.version)
+            .map(|f| f.name.clone())
+            .collect()
+    }
+
+    pub(crate) fn find_by_fileds(&self) -> (Vec<Ident>, Vec<Type>) {
+        let mut ids = vec![];
+        let mut tys = vec![];
+        for f in self.fields.iter().filter(|f| f.find) {
+            ids.push(f.name.clone());
+            tys.push(f.ty.clone());
+        }
+        (ids, tys)
+    }
+    pub(crate) fn find_filter_fileds(&self) -> (Vec<Ident>, Vec<Type>, Vec<bool>) {
+        let mut ids = vec![];
+        let mut tys = vec![];
+        let mut opts_like = vec![];
+        for f in self.fields.iter().filter(|f| f.find_opt) {
+            ids.push(f.name.clone());
+            tys.push(f.ty.clone());
+            opts_like.push(f.opt_like);
+        }
+        (ids, tys, opts_like)
+    }
+
+    pub(crate) fn all_fields(&self) -> Vec<Ident> {
+        self.fields.iter().map(|f| f.name.clone()).collect()
+    }
+
+    pub(crate) fn list_filter_fields(&self) -> (Vec<Ident>, Vec<Type>) {
+        let mut ids = vec![];
+        let mut tys = vec![];
+
+        for f in self.fields.iter().filter(|f| f.list) {
+            ids.push(f.name.clone());
+            tys.push(f.ty.clone());
+        }
+        (ids, tys)
+    }
+    pub(crate) fn list_filter_fields_opt(&self) -> (Vec<Ident>, Vec<Type>, Vec<bool>) {
+        let mut ids = vec![];
+        let mut tys = vec![];
+        let mut ols = vec![];
+
+        for f in self.fields.iter().filter(|f| f.list_opt) {
+            ids.push(f.name.clone());
+            tys.push(f.ty.clone());
+            ols.push(f.opt_like);
+        }
+        (ids, tys, ols)
+    }
+
+    pub(crate) fn version_field(&self) -> Option<&DbField> {
+        self.fields.iter().find(|f| f.version)
+    }
+
+    pub(crate) fn patch_ident(&self) -> Ident {
+        Ident::new(&format!("{}Patch", self.ident), self.ident.span())
+    }
+
+    pub(crate) fn error_ident(&self) -> Ident {
+        Ident::new(&format!("{}Error", self.ident), self.ident.span())
+    }
+
+    /// 数据库方法的返回类型：`#[db(db_error)]` 时为 `Result<ok, {Name}Error>`，否则为 `sqlx::Result<ok>`
+    pub(crate) fn result_ts(&self, ok: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.db_error {
+            let error_ident = self.error_ident();
+            quote! { ::std::result::Result<#ok, #error_ident> }
+        } else {
+            let krate = &self.krate;
+            quote! { #krate::sqlx::Result<#ok> }
+        }
+    }
+
+    /// 以 `sqlx::Result` 作为方法返回值时，按需转换为 `{Name}Error`
+    pub(crate) fn tail_ts(&self, expr: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.db_error {
+            let error_ident = self.error_ident();
+            quote! { #expr.map_err(#error_ident::from) }
+        } else {
+            expr
+        }
+    }
+
+    /// 过滤条件结构体的额外 derive，`with_default` 为 `false` 时排除 `Default`
+    pub(crate) fn filter_derive_ts(&self, with_default: bool) -> proc_macro2::TokenStream {
+        let paths = self
+            .filter_derive
+            .iter()
+            .filter(|p| with_default || !_path_is(p, "Default"))
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return quote! {};
+        }
+        quote! { #[derive(#(#paths),*)] }
+    }
+    /// 生成路由时，为未声明 `Deserialize` 的类型补充 `Deserialize`
+    pub(crate) fn router_de_ts(&self, declared: bool) -> proc_macro2::TokenStream {
+        if !self.router || declared {
+            return quote! {};
+        }
+        let krate = &self.krate;
+        let serde_crate = format!("{}::serde", quote! { #krate }).replace(' ', "");
+        quote! {
+            #[derive(#krate::serde::Deserialize)]
+            #[serde(crate = #serde_crate)]
+        }
+    }
+    pub(crate) fn filter_derive_serde(&self) -> bool {
+        self.filter_derive
+            .iter()
+            .any(|p| _path_is(p, "Deserialize") || _path_is(p, "Serialize"))
+    }
+
+    /// 字段的文档注释，用于生成类型的字段说明
+    pub(crate) fn field_docs(&self, fields: &[Ident]) -> Vec<proc_macro2::TokenStream> {
+        fields
+            .iter()
+            .map(|n| {
+                let docs = self
+                    .fields
+                    .iter()
+                    .find(|f| &f.name == n)
+                    .map(|f| f.docs.clone())
+                    .unwrap_or_default();
+                quote! { #(#docs)* }
+            })
+            .collect()
+    }
+
+    /// 声明 `#[db(utoipa)]` 时，为生成的类型派生 `ToSchema`，`params` 为 `true` 时同时派生 `IntoParams`
+    ///
+    /// 通过 `{crate}::utoipa` 引用，需启用 `utoipa` 特性
+    pub(crate) fn utoipa_ts(&self, params: bool) -> proc_macro2::TokenStream {
+        if !self.utoipa {
+            return quote! {};
+        }
+        let krate = &self.krate;
+        if params {
+            quote! {
+                #[derive(#krate::utoipa::ToSchema, #krate::utoipa::IntoParams)]
+                #[into_params(parameter_in = Query)]
+            }
+        } else {
+            quote! { #[derive(#krate::utoipa::ToSchema)] }
+        }
+    }
+
+    pub(crate) fn cond_ident(&self) -> Ident {
+        Ident::new(&format!("{}Cond", self.ident), self.ident.span())
+    }
+
+    /// 过滤条件中 `cond` 字段的属性：不参与序列化，也不出现在 OpenAPI 文档中
+    pub(crate) fn cond_attr_ts(&self, serde: bool, params: bool) -> proc_macro2::TokenStream {
+        let serde = if serde {
+            quote! { #[serde(skip)] }
+        } else {
+            quote! {}
+        };
+        let utoipa = match (self.utoipa, params) {
+            (true, true) => quote! { #[schema(ignore)] #[param(ignore)] },
+            (true, false) => quote! { #[schema(ignore)] },
+            _ => quote! {},
+        };
+        quote! { #serde #utoipa }
+    }
+
+    pub(crate) fn pk_ident(&self) -> Ident {
+        Ident::new(&self.pk, self.ident.clone().span())
+    }
+    pub(crate) fn pk_type(&self) -> Type {
+        self.fields
+            .iter()
+            .find(|f| f.name == self.pk)
+            .unwrap()
+            .ty
+            .clone()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct DbMetaParser {
+    pub(crate) table: Option<LitStr>,
+    pub(crate) pk: Option<LitStr>,
+    pub(crate) is_view: bool,
+    pub(crate) krate: Option<Path>,
+    pub(crate) max_page_size: Option<LitInt>,
+    pub(crate) filter_derive: Vec<Path>,
+    pub(crate) router: bool,
+    pub(crate) hooks: Hooks,
+    pub(crate) db_error: bool,
+    pub(crate) utoipa: bool,
+    pub(crate) ddl: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct DbField {
+    pub(crate) name: Ident,
+    pub(crate) ty: Type,
+    pub(crate) skip_update: bool,
+    pub(crate) skip_insert: bool,
+    pub(crate) find: bool,
+    pub(crate) find_opt: bool,
+    pub(crate) list: bool,
+    pub(crate) list_opt: bool,
+    pub(crate) opt_like: bool,
+    pub(crate) aggregate: bool,
+    pub(crate) facet: bool,
+    pub(crate) version: bool,
+    pub(crate) unique: bool,
+    pub(crate) default: Option<String>,
+    pub(crate) sql_type: Option<String>,
+    pub(crate) index: Option<String>,
+    /// 导出时的表头
+    pub(crate) rename: Option<String>,
+    pub(crate) validate: Vec<Rule>,
+    pub(crate) docs: Vec<Attribute>,
+}
+
+/// 解析字段
+pub(crate) fn parse_fields(ast: &DeriveInput) -> Punctuated<Field, Comma> {
+    if let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(syn::FieldsNamed { named, .. }, ..),
+        ..
+    }) = &ast.data
+    {
+        named.to_owned()
+    } else {
+        unreachable!()
+    }
+}
+
+/// 解析数据表元数据
+pub(crate) fn parse_db_meta(ast: &DeriveInput) -> DbMeta {
+    let mut dmp = DbMetaParser::default();
+    let ident = ast.ident.clone();
+    let ident_str = ident.to_string();
+    let table = _gen_table_name(&ident_str);
+    let mut dm = DbMeta {
+        ident,
+        table,
+        pk: "id".to_string(),
+        is_view: false,
+        krate: syn::parse_quote!(::db_derive),
+        max_page_size: None,
+        filter_derive: vec![],
+        router: false,
+        hooks: Hooks::default(),
+        db_error: false,
+        utoipa: false,
+        ddl: false,
+        fields: vec![],
+    };
+
+    for a in ast.attrs.iter() {
+        if let syn::Meta::List(syn::MetaList { path, tokens, .. }) = &a.meta {
+            if let Some(seg) = path.segments.first() {
+                if seg.ident == "db" {
+                    _db_meta_parser(tokens, &mut dmp);
+                    if let Some(v) = &dmp.table {
+                        dm.table = v.token().to_string().replace("\"", "");
+                    }
+                    if let Some(v) = &dmp.pk {
+                        dm.pk = v.token().to_string().replace("\"", "");
+                    }
+                    dm.is_view = dmp.is_view;
+                    if let Some(v) = &dmp.krate {
+                        dm.krate = v.clone();
+                    }
+                    if let Some(v) = &dmp.max_page_size {
+                        dm.max_page_size = Some(v.base10_parse().unwrap());
+                    }
+                    dm.filter_derive = dmp.filter_derive.clone();
+                    dm.router = dmp.router;
+                    dm.hooks = dmp.hooks.clone();
+                    dm.db_error = dmp.db_error;
+                    dm.utoipa = dmp.utoipa;
+                    dm.ddl = dmp.ddl;
+                }
+            }
+        }
+    }
+
+    // 解析字段
+    let meta_fields = parse_fields(ast);
+
+    let mut fields = vec![];
+
+    // 字段属性
+    for f in meta_fields {
+        let name = f.ident.clone();
+        let ty = f.ty.clone();
+        let attrs = f
+            .attrs
+            .clone()
+            .into_iter()
+            .filter(|a| a.path().is_ident("db"))
+            .collect::<Vec<_>>();
+
+        let mut db_field = DbField {
+            name: name.unwrap(),
+            ty,
+            skip_insert: false,
+            skip_update: false,
+            find: false,
+            find_opt: false,
+            list: false,
+            list_opt: false,
+            opt_like: false,
+            aggregate: false,
+            facet: false,
+            version: false,
+            unique: false,
+            default: None,
+            sql_type: None,
+            index: None,
+            rename: None,
+            validate: vec![],
+            docs: f
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("doc"))
+                .cloned()
+                .collect(),
+        };
+
+        // 解析字段属性
+        for a in attrs.iter() {
+            a.parse_nested_meta(|mt| {
+                if mt.path.is_ident("skip_update") {
+                    db_field.skip_update = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("skip_insert") {
+                    db_field.skip_insert = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("find") {
+                    db_field.find = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("find_opt") {
+                    db_field.find_opt = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("list") {
+                    db_field.list = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("list_opt") {
+                    db_field.list_opt = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("opt_like") {
+                    db_field.opt_like = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("aggregate") {
+                    db_field.aggregate = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("facet") {
+                    db_field.facet = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("version") {
+                    db_field.version = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("unique") {
+                    db_field.unique = true;
+                    return Ok(());
+                }
+                if mt.path.is_ident("default") {
+                    let v: LitStr = mt.value()?.parse()?;
+                    db_field.default = Some(v.value());
+                    return Ok(());
+                }
+                if mt.path.is_ident("index") {
+                    let v: LitStr = mt.value()?.parse()?;
+                    db_field.index = Some(v.value());
+                    return Ok(());
+                }
+                if mt.path.is_ident("validate") {
+                    return parse_rules(&mt, &mut db_field.validate);
+                }
+                if mt.path.is_ident("rename") {
+                    let v: LitStr = mt.value()?.parse()?;
+                    db_field.rename = Some(v.value());
+                    return Ok(());
+                }
+                if mt.path.is_ident("sql_type") {
+                    let v: LitStr = mt.value()?.parse()?;
+                    db_field.sql_type = Some(v.value());
+                    return Ok(());
+                }
+
+                Ok(())
+            })
+            .unwrap();
+        }
+        fields.push(db_field);
+    }
+
+    dm.fields = fields;
+
+    dm
+}
+
+/// 生成表名
+fn _gen_table_name(tn: &str) -> String {
+    let mut ss = String::new();
+
+    for (idx, c) in tn.chars().enumerate() {
+        if idx == 0 {
+            ss.push(c);
+            continue;
+        }
+        if c.is_lowercase() {
+            ss.push(c);
+            continue;
+        }
+        if c.is_uppercase() {
+            ss.extend(['_', c]);
+            continue;
+        }
+    }
+
+    ss.push('s');
+
+    ss.to_lowercase()
+}
+
+/// 生成蛇形命名，如 `UserMessage` => `user_message`
+pub(crate) fn _gen_snake_name(tn: &str) -> String {
+    let mut ss = String::new();
+
+    for (idx, c) in tn.chars().enumerate() {
+        if idx > 0 && c.is_uppercase() {
+            ss.push('_');
+        }
+        ss.push(c);
+    }
+
+    ss.to_lowercase()
+}
+
+/// 解析元数据
+fn _db_meta_parser(
+    tokens: &proc_macro2::TokenStream,
+    dmp: &mut DbMetaParser,
+) -> proc_macro::TokenStream {
+    let parser = syn::meta::parser(|mt| {
+        if mt.path.is_ident("table") {
+            dmp.table = Some(mt.value()?.parse()?);
+            return Ok(());
+        }
+        if mt.path.is_ident("pk") {
+            dmp.pk = Some(mt.value()?.parse()?);
+            return Ok(());
+        }
+        if mt.path.is_ident("is_view") {
+            dmp.is_view = true;
+            return Ok(());
+        }
+        if mt.path.is_ident("crate") {
+            let v: LitStr = mt.value()?.parse()?;
+            dmp.krate = Some(v.parse()?);
+            return Ok(());
+        }
+        if mt.path.is_ident("max_page_size") {
+            dmp.max_page_size = Some(mt.value()?.parse()?);
+            return Ok(());
+        }
+        if mt.path.is_ident("router") {
+            dmp.router = true;
+            return Ok(());
+        }
+        if mt.path.is_ident("db_error") {
+            dmp.db_error = true;
+            return Ok(());
+        }
+        if mt.path.is_ident("utoipa") {
+            dmp.utoipa = true;
+            return Ok(());
+        }
+        if mt.path.is_ident("ddl") {
+            dmp.ddl = true;
+            return Ok(());
+        }
+        if let Some(hook) = mt
+            .path
+            .get_ident()
+            .and_then(|i| dmp.hooks.slot_mut(&i.to_string()))
+        {
+            let v: LitStr = mt.value()?.parse()?;
+            *hook = Some(v.parse()?);
+            return Ok(());
+        }
+        if mt.path.is_ident("filter_derive") {
+            mt.parse_nested_meta(|d| {
+                dmp.filter_derive.push(d.path);
+                Ok(())
+            })?;
+            return Ok(());
+        }
+
+        Ok(())
+    });
+
+    let tokens = tokens.to_owned().into();
+    parse_macro_input!(tokens with parser);
+
+    quote! {}.into()
+}
+
+pub(crate) fn const_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let table = &dm.table;
+    let pk = &dm.pk;
+    let columns = dm
+        .all_fields()
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+    let insert_columns = dm
+        .insert_fileds()
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+    let update_columns = dm
+        .update_fileds()
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+    let column_list = columns
+        .iter()
+        .map(|f| format!("{:?}", f))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    quote! {
+        /// 表名
+        pub const TABLE: &'static str = #table;
+        /// 主键
+        pub const PK: &'static str = #pk;
+        /// 所有列
+        pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+        /// `insert` 写入的列
+        pub const INSERT_COLUMNS: &'static [&'static str] = &[#(#insert_columns),*];
+        /// `update` 写入的列
+        pub const UPDATE_COLUMNS: &'static [&'static str] = &[#(#update_columns),*];
+        /// 用于 `SELECT` 的列清单，如 `"id", "name"`
+        pub const COLUMN_LIST: &'static str = #column_list;
+    }
+}
+
+pub(crate) fn insert_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    if dm.is_view {
+        panic!("视图不提供插入方法");
+    }
+    let field_list = dm.insert_fileds();
+    let field_list_str = field_list
+        .iter()
+        .map(|f| format!(r#""{}""#, f))
+        .collect::<Vec<_>>()
+        .join(",");
+    let table = dm.table.clone();
+    let sql = format!("INSERT INTO {:?} ({})", &table, &field_list_str);
+    let returning = format!(" RETURNING {:?}", &dm.pk);
+    let pk_type = dm.pk_type();
+    let (vis, name) = dm.hooks.raw_method("insert");
+    let (ret, validate) = dm.validated_write_ts(quote! { #pk_type });
+
+    quote! {
+        /// 插入一条记录，返回数据库中的主键（包括自增主键）
+        #vis async fn #name<'a>(&self, e: impl  #krate::sqlx::PgExecutor<'a>) -> #ret {
+            #validate
+           let sql = #sql;
+           let mut q = #krate::sqlx::QueryBuilder::new(sql);
+           q.push_values(&[self], |mut b, m| {
+                #(b.push_bind(&m.#field_list);)*
+           });
+           q.push(#returning);
+            let id: #pk_type = q.build_query_scalar().fetch_one(e).await?;
+            Ok(id)
+        }
+    }
+}
+
+pub(crate) fn copy_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let error_ident = dm.error_ident();
+    let field_list = dm.insert_fileds();
+    let field_count = field_list.len() as i16;
+    let field_list_str = field_list
+        .iter()
+        .map(|f| format!("{:?}", f.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql_in = format!(
+        "COPY {:?} ({}) FROM STDIN (FORMAT binary)",
+        &dm.table, &field_list_str
+    );
+    let fields_str = dm
+        .all_fields()
+        .iter()
+        .map(|f| format!("{:?}", f.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql_out = format!(
+        "COPY (SELECT {} FROM {:?}) TO STDOUT ",
+        &fields_str, &dm.table
+    );
+    // 有校验规则时逐行校验，失败则中止 COPY
+    let (copy_in_ret, copy_in_tail, copy_validate) = if dm.has_validation() {
+        (
+            quote! { ::std::result::Result<u64, #error_ident> },
+            quote! { copy.finish().await.map_err(#error_ident::from) },
+            quote! {
+                if let Err(e) = row.validate() {
+                    copy.abort(e.to_string()).await?;
+                    return Err(e.into());
+                }
+            },
+        )
+    } else {
+        (
+            dm.result_ts(quote! { u64 }),
+            dm.tail_ts(quote! { copy.finish().await }),
+            quote! {},
+        )
+    };
+
+    quote! {
+        /// 通过 `COPY ... FROM STDIN` 以二进制格式批量写入，返回写入的行数
+        ///
+        /// 任一行校验失败时中止 COPY，不写入任何数据；在事务中调用时该事务随之失败
+        pub async fn copy_in<R: ::std::borrow::Borrow<Self>>(conn: &mut #krate::sqlx::PgConnection, rows: impl IntoIterator<Item = R>) -> #copy_in_ret {
+            let mut copy = conn.copy_in_raw(#sql_in).await?;
+            let mut enc = #krate::CopyEncoder::new();
+            for row in rows {
+                let row = row.borrow();
+                #copy_validate
+                enc.row(#field_count);
+                #( enc.field(&row.#field_list); )*
+                if enc.len() >= #krate::COPY_CHUNK_SIZE {
+                    copy.send(enc.take()).await?;
+                }
+            }
+            copy.send(enc.finish()).await?;
+            #copy_in_tail
+        }
+        /// 通过 `COPY ... TO STDOUT` 导出全部记录，数据原样写入 `w`，返回写入的字节数
+        pub async fn copy_out(conn: &mut #krate::sqlx::PgConnection, format: #krate::CopyFormat, w: &mut impl ::std::io::Write) -> ::std::result::Result<u64, #error_ident> {
+            let sql = [#sql_out, format.as_sql()].concat();
+            #krate::copy_out(conn, &sql, w).await
+        }
+    }
+}
+
+pub(crate) fn update_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let field_list = dm.update_fileds();
+    let version = dm.version_field();
+    let field_list_str = field_list
+        .iter()
+        .map(|f| format!("{:?} = ", f.to_string()))
+        .collect::<Vec<_>>();
+    let field_list_com = field_list
+        .iter()
+        .enumerate()
+        .map(|(idx, _)| {
+            if idx < field_list.len() - 1 || version.is_some() {
+                ", "
+            } else {
+                ""
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let table = dm.table.clone();
+    let sql = format!("UPDATE {:?} SET ", &table,);
+    let pk = dm.pk_ident().clone();
+    let pk_where = format!(" WHERE {:?} = ", pk.to_string());
+    let (vis, name) = dm.hooks.raw_method("update");
+    let (ret, validate) = dm.validated_write_ts(quote! { u64 });
+
+    // 乐观锁：版本号自增，并以旧版本号作为更新条件
+    if let Some(v) = version {
+        let version = &v.name;
+        let version_ty = &v.ty;
+        let version_str = version.to_string();
+        let version_set = format!("{:?} = {:?} + 1", &version_str, &version_str);
+        let version_where = format!(" AND {:?} = ", &version_str);
+        let version_returning = format!(" RETURNING {:?}", &version_str);
+        let error_ident = dm.error_ident();
+
+        return quote! {
+            #vis async fn #name<'a>(&mut self, e: impl  #krate::sqlx::PgExecutor<'a>) -> ::std::result::Result<u64, #error_ident> {
+                #validate
+                let sql = #sql;
+                let mut q = #krate::sqlx::QueryBuilder::new(sql);
+                #(
+                    q.push(#field_list_str)
+                    .push_bind(&self.#field_list)
+                    .push(#field_list_com);
+                )*
+                q.push(#version_set);
+
+                q.push(#pk_where).push_bind(&self.#pk);
+                q.push(#version_where).push_bind(&self.#version);
+                q.push(#version_returning);
+
+                let v: ::std::option::Option<#version_ty> = q.build_query_scalar().fetch_optional(e).await?;
+                match v {
+                    Some(v) => {
+                        self.#version = v;
+                        Ok(1)
+                    }
+                    None => Err(#error_ident::StaleObject),
+                }
+            }
+        };
+    }
+
+    quote! {
+        #vis async fn #name<'a>(&self, e: impl  #krate::sqlx::PgExecutor<'a>) -> #ret {
+            #validate
+            let sql = #sql;
+            let mut q = #krate::sqlx::QueryBuilder::new(sql);
+            #(
+                q.push(#field_list_str)
+                .push_bind(&self.#field_list)
+                .push(#field_list_com);
+            )*
+
+            q.push(#pk_where).push_bind(&self.#pk);
+
+            let aff = q.build().execute(e).await?.rows_affected();
+            Ok(aff)
+        }
+    }
+}
+pub(crate) fn update_one_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let error_ident = dm.error_ident();
+    let receiver = dm.hooked_receiver_ts("update", dm.version_field().is_some());
+
+    quote! {
+        /// 按主键更新，且必须恰好影响一行
+        pub async fn update_one<'a>(#receiver) -> ::std::result::Result<(), #error_ident> {
+            match self.update(e).await? {
+                0 => Err(#error_ident::NotFound),
+                1 => Ok(()),
+                n => Err(#error_ident::TooManyRows(n)),
+            }
+        }
+    }
+}
+
+pub(crate) fn del_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let table = dm.table.clone();
+    let pk = dm.pk_ident().clone();
+    let pk_str = pk.to_string();
+    let sql = format!("DELETE FROM {:?} WHERE {:?} = ", &table, &pk_str);
+    let error_ident = dm.error_ident();
+    let pk_type = dm.pk_type();
+    let (vis, name) = dm.hooks.raw_method("delete");
+    let receiver = dm.hooked_receiver_ts("delete", false);
+    let ret = dm.result_ts(quote! { u64 });
+
+    quote! {
+        #vis async fn #name<'a>(&self, e: impl  #krate::sqlx::PgExecutor<'a>) -> #ret {
+            let sql = #sql;
+            let mut q = #krate::sqlx::QueryBuilder::new(sql);
+            q.push_bind(&self.#pk);
+            let aff = q.build().execute(e).await?.rows_affected();
+            Ok(aff)
+        }
+
+        /// 按主键值删除，不执行删除钩子
+        pub async fn delete_by_pk<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, id: &#pk_type) -> #ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            q.push_bind(id);
+            let aff = q.build().execute(e).await?.rows_affected();
+            Ok(aff)
+        }
+
+        /// 按主键删除，且必须恰好影响一行
+        pub async fn delete_one<'a>(#receiver) -> ::std::result::Result<(), #error_ident> {
+            match self.delete(e).await? {
+                0 => Err(#error_ident::NotFound),
+                1 => Ok(()),
+                n => Err(#error_ident::TooManyRows(n)),
+            }
+        }
+    }
+}
+
+pub(crate) fn error_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let error_ident = dm.error_ident();
+
+    quote! {
+        pub type #error_ident = #krate::Error;
+    }
+}
+
+pub(crate) fn find_by_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let ident = &dm.ident;
+    let find_by_ident_str = format!("{}FindBy", ident);
+    let find_by_ident = Ident::new(&find_by_ident_str, dm.ident.span());
+    let (find_by_origin_fields, find_by_types) = dm.find_by_fileds();
+    let find_by_fields = find_by_origin_fields
+        .iter()
+        .map(|f| _gen_entity_ident(f.to_owned()))
+        .collect::<Vec<_>>();
+    let find_filter_ident_str = format!("{}FindFilter", ident);
+    let find_filter_ident = Ident::new(&find_filter_ident_str, dm.ident.span());
+    let (find_filter_fields, find_filter_types, _) = dm.find_filter_fileds();
+    let find_by_docs = dm.field_docs(&find_by_origin_fields);
+    let find_filter_docs = dm.field_docs(&find_filter_fields);
+    // 枚举无法派生 Default
+    let filter_derive = dm.filter_derive_ts(false);
+    let utoipa = dm.utoipa_ts(false);
+    let cond_ident = dm.cond_ident();
+    let cond_attr = dm.cond_attr_ts(dm.filter_derive_serde(), false);
+    quote! {
+        #filter_derive
+        #utoipa
+        pub enum #find_by_ident {
+            #( #find_by_docs #find_by_fields(#find_by_types), )*
+        }
+        #filter_derive
+        #utoipa
+        pub struct #find_filter_ident {
+            pub by: #find_by_ident,
+            #( #find_filter_docs pub #find_filter_fields: ::std::option::Option<#find_filter_types>,)*
+            /// 附加的组合条件
+            #cond_attr
+            pub cond: ::std::option::Option<#cond_ident>,
+        }
+    }
+}
+
+pub(crate) fn find_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = dm.ident.clone();
+    let ident_str = ident.to_string();
+
+    let find_by_ident_str = format!("{}FindBy", &ident_str);
+    let find_by_ident = Ident::new(&find_by_ident_str, ident.span());
+    let find_ident_str = format!("{}FindFilter", &ident_str);
+    let find_ident = Ident::new(&find_ident_str, ident.span());
+
+    let (find_by_origin_fields, _) = dm.find_by_fileds();
+    let find_by_fields = find_by_origin_fields
+        .iter()
+        .map(|f| _gen_entity_ident(f.to_owned()))
+        .collect::<Vec<_>>();
+    let find_by_fields_str = find_by_origin_fields
+        .iter()
+        .map(|f| format!(" AND {:?} = ", f.to_string()))
+        .collect::<Vec<_>>();
+
+    let (find_filter_fields, _, find_filter_opt_like) = dm.find_filter_fileds();
+    let find_filter_push = find_filter_fields
+        .iter()
+        .zip(find_filter_opt_like)
+        .map(|(f, like)| _filter_push_ts(f, like))
+        .collect::<Vec<_>>();
+
+    let fields = dm.all_fields();
+    let fields_str_arr = fields
+        .iter()
+        .map(|f| format!("{:?}", f.to_string()))
+        .collect::<Vec<_>>();
+    let fields_str = fields_str_arr.join(", ");
+    let sql = format!("SELECT {} FROM {:?} WHERE 1=1", &fields_str, &dm.table);
+    let sql_pk = format!(
+        "SELECT {} FROM {:?} WHERE {:?} = ",
+        &fields_str, &dm.table, &dm.pk
+    );
+    let pk_type = dm.pk_type();
+    let find_ret = dm.result_ts(quote! { ::std::option::Option<Self> });
+    let fetch_optional = dm.tail_ts(quote! { q.build_query_as().fetch_optional(e).await });
+
+    quote! {
+        pub async fn find<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, f:&#find_ident) -> #find_ret {
+
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            Self::push_find_filter(&mut q, f);
+
+            #fetch_optional
+        }
+
+        /// 将查找条件追加到查询
+        fn push_find_filter<'q>(q: &mut #krate::sqlx::QueryBuilder<'q, #krate::sqlx::Postgres>, f: &'q #find_ident) {
+             match &f.by {
+                    #( #find_by_ident::#find_by_fields(v) => { q.push(#find_by_fields_str).push_bind(v); }, )*
+                };
+
+            #(
+                if let Some(v) = &f.#find_filter_fields {
+                    #find_filter_push
+                }
+            )*
+
+            if let Some(c) = &f.cond {
+                q.push(" AND ");
+                c.push_to(q);
+            }
+        }
+
+        /// 按主键查找
+        pub async fn find_by_pk<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, id: &#pk_type) -> #find_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql_pk);
+            q.push_bind(id);
+            #fetch_optional
+        }
+    }
+}
+
+pub(crate) fn list_filter_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = dm.ident.clone();
+    let ident_str = ident.to_string();
+    let filter_ident_str = format!("{}ListFilter", &ident_str);
+    let filter_ident = Ident::new(&filter_ident_str, ident.span());
+    let (filter_fields, filter_types) = dm.list_filter_fields();
+    let (filter_fields_opt, filter_types_opt, filter_like_opt) = dm.list_filter_fields_opt();
+    let paginate_ident_str = format!("{}Paginate", &ident_str);
+    let paginate_ident = Ident::new(&paginate_ident_str, ident.span());
+    let paginate_req_ident_str = format!("{}PaginateReq", &ident_str);
+    let paginate_req_ident = Ident::new(&paginate_req_ident_str, ident.span());
+
+    // 存在必填过滤字段时，过滤条件永远不为空；`opt_like` 字段为空字符串时视为未设置
+    let filter_unset = filter_fields_opt
+        .iter()
+        .zip(&filter_like_opt)
+        .map(|(f, like)| {
+            if *like {
+                quote! { !matches!(&self.#f, Some(v) if !::std::convert::AsRef::<str>::as_ref(v).is_empty()) }
+            } else {
+                quote! { self.#f.is_none() }
+            }
+        })
+        .collect::<Vec<_>>();
+    let filter_is_empty = if filter_fields.is_empty() {
+        quote! { self.cond.is_none() #( && #filter_unset )* }
+    } else {
+        quote! { false }
+    };
+
+    let filter_derive = dm.filter_derive_ts(true);
+    let router_de = dm.router_de_ts(dm.filter_derive.iter().any(|p| _path_is(p, "Deserialize")));
+    let utoipa = dm.utoipa_ts(true);
+    let filter_docs = dm.field_docs(&filter_fields);
+    let filter_docs_opt = dm.field_docs(&filter_fields_opt);
+    let cond_ident = dm.cond_ident();
+    let cond_attr = dm.cond_attr_ts(dm.filter_derive_serde() || dm.router, true);
+    // 分页参数平铺到查询字符串中：?page=2&page_size=20&user_id=x
+    let pq_attr = if dm.filter_derive_serde() || dm.router {
+        quote! { #[serde(flatten)] }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #[derive(Debug)]
+        #filter_derive
+        #router_de
+        #utoipa
+        pub struct #filter_ident {
+            #pq_attr
+            pub pq:#paginate_req_ident,
+            #( #filter_docs pub #filter_fields: #filter_types, )*
+            #( #filter_docs_opt pub #filter_fields_opt: ::std::option::Option<#filter_types_opt>, )*
+            /// 附加的组合条件
+            #cond_attr
+            pub cond: ::std::option::Option<#cond_ident>,
+        }
+        impl #filter_ident {
+            /// 是否未设置任何过滤字段
+            pub fn is_empty(&self) -> bool {
+                #filter_is_empty
+            }
+        }
+        pub type #paginate_req_ident = #krate::PaginateReq;
+        pub type #paginate_ident = #krate::Paginate<#ident>;
+    }
+}
+
+pub(crate) fn list_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = dm.ident.clone();
+    let ident_str = ident.to_string();
+    let filter_ident_str = format!("{}ListFilter", &ident_str);
+    let filter_ident = Ident::new(&filter_ident_str, ident.span());
+
+    let filter_push = list_filter_push_ts(dm, None);
+
+    let fields = dm.all_fields();
+    let fields_str_arr = fields
+        .iter()
+        .map(|f| format!("{:?}", f.to_string()))
+        .collect::<Vec<_>>();
+    let fields_str = fields_str_arr.join(", ");
+    let sql = format!("SELECT {} FROM {:?} WHERE 1=1", &fields_str, &dm.table);
+    let sql_count = format!("SELECT COUNT(*) FROM {:?} WHERE 1=1", &dm.table);
+    // 游标名加上当前会话中未被占用的最小序号：同一事务中可同时存在多个游标，
+    // 而语句文本只有少数几种，不会挤占连接的预处理语句缓存
+    let cursor_prefix = format!("{}_list_cursor_", _gen_snake_name(&ident_str));
+    let sql_slot = format!(
+        "SELECT min(i) FROM generate_series(0, (SELECT count(*)::int FROM pg_cursors)) AS i WHERE '{}' || i NOT IN (SELECT name FROM pg_cursors)",
+        &cursor_prefix
+    );
+    let sql_declare = format!(" NO SCROLL CURSOR FOR {}", &sql);
+
+    let paginate_ident_str = format!("{}Paginate", &ident_str);
+    let paginate_ident = Ident::new(&paginate_ident_str, ident.span());
+    let max_page_size = match dm.max_page_size {
+        Some(v) => quote! { #v },
+        None => quote! { #krate::MAX_PAGE_SIZE },
+    };
+    let list_ret = dm.result_ts(quote! { #paginate_ident });
+    let data_ret = dm.result_ts(quote! { Vec<#ident> });
+    let row_ret = dm.result_ts(quote! { #ident });
+    let count_ret = dm.result_ts(quote! { i64 });
+    let fetch_all = dm.tail_ts(quote! { q.build_query_as().fetch_all(e).await });
+
+    quote! {
+        pub async fn list(p:&#krate::sqlx::PgPool, f:&#filter_ident) -> #list_ret {
+            let pq = f.pq.clamp(#max_page_size);
+            let mut tx = p.begin().await?;
+            let data = Self::list_page_data(&mut *tx,f,&pq).await?;
+            let count = Self::list_count(&mut *tx,f).await?;
+            tx.commit().await?;
+            Ok(#paginate_ident::quick(count,&pq,data))
+        }
+        async fn list_page_data<'a>(e: impl  #krate::sqlx::PgExecutor<'a>,f:&#filter_ident,pq:&#krate::PaginateReq) -> #data_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            Self::push_list_filter(&mut q, f);
+            q.push(" LIMIT ").push_bind(pq.limit());
+            q.push(" OFFSET ").push_bind(pq.offset());
+
+            #fetch_all
+        }
+        pub async fn list_data<'a>(e: impl  #krate::sqlx::PgExecutor<'a>,f:&#filter_ident) -> #data_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            Self::push_list_filter(&mut q, f);
+
+            #fetch_all
+        }
+        /// 以流的方式逐行读取符合条件的全部记录，适用于大批量导出
+        pub fn list_stream<'a>(e: impl #krate::sqlx::PgExecutor<'a> + 'a, f: &'a #filter_ident) -> impl #krate::Stream<Item = #row_ret> + Send + 'a {
+            #krate::async_stream::try_stream! {
+                let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+                Self::push_list_filter(&mut q, f);
+
+                for await row in q.build_query_as::<#ident>().fetch(e) {
+                    yield row?;
+                }
+            }
+        }
+        /// 通过服务端游标分批读取符合条件的全部记录，每批 `batch` 行
+        ///
+        /// 游标只在事务内有效，`conn` 需为已开启事务的连接；流未读完即丢弃时，游标随事务结束关闭
+        pub fn list_stream_cursor<'a>(conn: &'a mut #krate::sqlx::PgConnection, f: &'a #filter_ident, batch: u32) -> impl #krate::Stream<Item = #row_ret> + Send + 'a {
+            #krate::async_stream::try_stream! {
+                let slot: i32 = #krate::sqlx::query_scalar(#sql_slot).fetch_one(&mut *conn).await?;
+                let cursor = format!("{}{}", #cursor_prefix, slot);
+
+                let mut q = #krate::sqlx::QueryBuilder::new(format!("DECLARE {:?}", cursor));
+                q.push(#sql_declare);
+                Self::push_list_filter(&mut q, f);
+                q.build().execute(&mut *conn).await?;
+
+                let sql_fetch = format!("FETCH {} FROM {:?}", batch.max(1), cursor);
+                loop {
+                    let rows: Vec<#ident> = #krate::sqlx::query_as(&sql_fetch).fetch_all(&mut *conn).await?;
+                    if rows.is_empty() {
+                        break;
+                    }
+                    for row in rows {
+                        yield row;
+                    }
+                }
+
+                #krate::sqlx::query(&format!("CLOSE {:?}", cursor)).execute(&mut *conn).await?;
+            }
+        }
+        pub async fn list_count<'a>(e: impl  #krate::sqlx::PgExecutor<'a>,f:&#filter_ident) -> #count_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql_count);
+            Self::push_list_filter(&mut q, f);
+
+            let count:(i64,)=q.build_query_as().fetch_one(e).await?;
+            Ok(count.0)
+        }
+
+        /// 将列表过滤条件追加到查询，查询需以 `WHERE 1=1` 结尾
+        pub fn push_list_filter<'q>(q: &mut #krate::sqlx::QueryBuilder<'q, #krate::sqlx::Postgres>, f: &'q #filter_ident) {
+            #filter_push
+        }
+    }
+}
+
+/// 追加列表过滤条件的语句，`q` 为 `&mut QueryBuilder`，`f` 为过滤条件；`skip` 指定的字段不参与过滤
+pub(crate) fn list_filter_push_ts(dm: &DbMeta, skip: Option<&Ident>) -> proc_macro2::TokenStream {
+    let (filter_fields, _) = dm.list_filter_fields();
+    let filter_fields = filter_fields
+        .into_iter()
+        .filter(|f| Some(f) != skip)
+        .collect::<Vec<_>>();
+    let filter_fields_str = filter_fields
+        .iter()
+        .map(|f| format!(" AND {:?} = ", f.to_string()))
+        .collect::<Vec<_>>();
+    let (filter_fields_opt, _, filter_like_opt) = dm.list_filter_fields_opt();
+    let (filter_fields_opt, filter_push_opt): (Vec<_>, Vec<_>) = filter_fields_opt
+        .iter()
+        .zip(filter_like_opt)
+        .filter(|(f, _)| Some(*f) != skip)
+        .map(|(f, like)| (f.clone(), _filter_push_ts(f, like)))
+        .unzip();
+
+    quote! {
+        #(
+            q.push(#filter_fields_str).push_bind(&f.#filter_fields);
+        )*
+
+        #(
+            if let Some(v) = &f.#filter_fields_opt {
+                #filter_push_opt
+            }
+        )*
+
+        if let Some(c) = &f.cond {
+            q.push(" AND ");
+            c.push_to(q);
+        }
+    }
+}
+
+pub(crate) fn patch_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let ident = &dm.ident;
+    let patch_ident = dm.patch_ident();
+    let fields = dm.update_fileds();
+    let types = dm
+        .fields
+        .iter()
+        .filter(|f| fields.contains(&f.name))
+        .map(|f| f.ty.clone())
+        .collect::<Vec<_>>();
+
+    let router_de = dm.router_de_ts(false);
+    let validate = patch_validate_ts(dm);
+    // 乐观锁：携带读取时的版本号，`patch` 以其作为更新条件
+    let version = dm.version_field().map(|v| {
+        let name = &v.name;
+        let ty = &v.ty;
+        quote! {
+            /// 读取时的版本号，与数据库中不一致时 `patch` 返回 `StaleObject`
+            pub #name: #ty,
+        }
+    });
+    let version_apply = dm.version_field().map(|v| {
+        let name = &v.name;
+        quote! { m.#name = self.#name; }
+    });
+
+    quote! {
+        #[derive(Debug, Default)]
+        #router_de
+        pub struct #patch_ident {
+            #( pub #fields: ::std::option::Option<#types>, )*
+            #version
+        }
+        impl #patch_ident {
+            pub fn is_empty(&self) -> bool {
+                true #( && self.#fields.is_none() )*
+            }
+            /// 将 `Some` 的字段写入 `m`，之后可通过 `update` 保存，以便执行钩子和校验
+            pub fn apply(self, m: &mut #ident) {
+                #(
+                    if let Some(v) = self.#fields {
+                        m.#fields = v;
+                    }
+                )*
+                #version_apply
+            }
+            #validate
+        }
+    }
+}
+
+pub(crate) fn where_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let filter_ident = Ident::new(&format!("{}ListFilter", dm.ident), dm.ident.span());
+    let patch_ident = dm.patch_ident();
+    let error_ident = dm.error_ident();
+
+    let fields = dm.update_fileds();
+    let fields_str = fields
+        .iter()
+        .map(|f| format!("{:?} = ", f.to_string()))
+        .collect::<Vec<_>>();
+    let version_set = dm.version_field().map(|v| {
+        let v = v.name.to_string();
+        let set = format!("{:?} = {:?} + 1", &v, &v);
+        quote! { s.push(#set); }
+    });
+
+    let del_sql = format!("DELETE FROM {:?} WHERE 1=1", &dm.table);
+    let update_sql = format!("UPDATE {:?} SET ", &dm.table);
+    let pk_where = format!(" WHERE {:?} = ", &dm.pk);
+    let pk_type = dm.pk_type();
+    // 有版本号时以旧版本号作为更新条件，未更新到记录即视为已被修改
+    let (patch_ret, version_where, patch_aff) = match dm.version_field() {
+        Some(v) => {
+            let name = &v.name;
+            let sql = format!(" AND {:?} = ", name.to_string());
+            (
+                quote! { ::std::result::Result<u64, #error_ident> },
+                quote! { q.push(#sql).push_bind(&p.#name); },
+                quote! {
+                    match aff {
+                        0 => Err(#error_ident::StaleObject),
+                        n => Ok(n),
+                    }
+                },
+            )
+        }
+        None if dm.has_patch_validation() => (
+            quote! { ::std::result::Result<u64, #error_ident> },
+            quote! {},
+            quote! { Ok(aff) },
+        ),
+        None => (dm.result_ts(quote! { u64 }), quote! {}, quote! { Ok(aff) }),
+    };
+    let patch_validate = dm.has_patch_validation().then(|| quote! { p.validate()?; });
+
+    quote! {
+        /// 按列表过滤条件批量删除，过滤条件为空时拒绝执行；不执行删除钩子
+        pub async fn delete_where<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, f: &#filter_ident) -> ::std::result::Result<u64, #error_ident> {
+            if f.is_empty() {
+                return Err(#error_ident::EmptyFilter);
+            }
+            let mut q = #krate::sqlx::QueryBuilder::new(#del_sql);
+            Self::push_list_filter(&mut q, f);
+
+            let aff = q.build().execute(e).await?.rows_affected();
+            Ok(aff)
+        }
+
+        /// 按列表过滤条件批量更新，过滤条件为空时拒绝执行；不执行更新钩子
+        ///
+        /// 不检查 `p` 中的版本号，匹配记录的版本号各自加一
+        pub async fn update_where<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, f: &#filter_ident, p: &#patch_ident) -> ::std::result::Result<u64, #error_ident> {
+            if f.is_empty() {
+                return Err(#error_ident::EmptyFilter);
+            }
+            if p.is_empty() {
+                return Ok(0);
+            }
+            #patch_validate
+            let mut q = #krate::sqlx::QueryBuilder::new(#update_sql);
+            {
+                let mut s = q.separated(", ");
+                #(
+                    if let Some(v) = &p.#fields {
+                        s.push(#fields_str).push_bind_unseparated(v);
+                    }
+                )*
+                #version_set
+            }
+            q.push(" WHERE 1=1");
+            Self::push_list_filter(&mut q, f);
+
+            let aff = q.build().execute(e).await?.rows_affected();
+            Ok(aff)
+        }
+
+        /// 按主键部分更新，只更新 `Some` 的字段；不执行更新钩子，需要时可使用 `Patch::apply` 后调用 `update`
+        ///
+        /// 有 `#[db(version)]` 字段时，版本号不一致或记录不存在均返回 `StaleObject`
+        pub async fn patch<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, id: &#pk_type, p: &#patch_ident) -> #patch_ret {
+            if p.is_empty() {
+                return Ok(0);
+            }
+            #patch_validate
+            let mut q = #krate::sqlx::QueryBuilder::new(#update_sql);
+            {
+                let mut s = q.separated(", ");
+                #(
+                    if let Some(v) = &p.#fields {
+                        s.push(#fields_str).push_bind_unseparated(v);
+                    }
+                )*
+                #version_set
+            }
+            q.push(#pk_where).push_bind(id);
+            #version_where
+
+            let aff = q.build().execute(e).await?.rows_affected();
+            #patch_aff
+        }
+    }
+}
+
+pub(crate) fn entity_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let table = &dm.table;
+    let pk_type = dm.pk_type();
+    let find_filter_ident = Ident::new(&format!("{}FindFilter", ident), ident.span());
+    let list_filter_ident = Ident::new(&format!("{}ListFilter", ident), ident.span());
+    let paginate_ident = Ident::new(&format!("{}Paginate", ident), ident.span());
+    let patch_ident = dm.patch_ident();
+    let error_ident = dm.error_ident();
+    // 在获取到的连接上调用同名方法；带钩子、版本号或 `db_error` 的方法已返回 `{Name}Error`
+    let write_body = |op: &str, returns_error: bool| {
+        let name = Ident::new(op, ident.span());
+        let call = if returns_error || dm.db_error {
+            quote! { Self::#name(self, &mut *conn).await }
+        } else {
+            quote! { Ok(Self::#name(self, &mut *conn).await?) }
+        };
+        quote! {
+            async move {
+                let mut conn = e.acquire().await?;
+                #call
+            }
+        }
+    };
+    let insert_body = write_body("insert", dm.hooks.has("insert") || dm.has_validation());
+    let update_body = write_body(
+        "update",
+        dm.hooks.has("update") || dm.version_field().is_some() || dm.has_validation(),
+    );
+    let delete_body = write_body("delete", dm.hooks.has("delete"));
+    let (find_body, list_body) = if dm.db_error {
+        (quote! { Self::find(e, f) }, quote! { Self::list(p, f) })
+    } else {
+        (
+            quote! { async move { Ok(Self::find(e, f).await?) } },
+            quote! { async move { Ok(Self::list(p, f).await?) } },
+        )
+    };
+
+    quote! {
+        impl #krate::Entity for #ident {
+            const TABLE: &'static str = #table;
+
+            type Pk = #pk_type;
+            type FindFilter = #find_filter_ident;
+            type ListFilter = #list_filter_ident;
+            type Patch = #patch_ident;
+            type Paginate = #paginate_ident;
+            type Error = #error_ident;
+
+            fn insert<'a>(&mut self, e: impl #krate::sqlx::Acquire<'a, Database = #krate::sqlx::Postgres> + Send) -> impl ::std::future::Future<Output = ::std::result::Result<Self::Pk, Self::Error>> + Send {
+                #insert_body
+            }
+            fn update<'a>(&mut self, e: impl #krate::sqlx::Acquire<'a, Database = #krate::sqlx::Postgres> + Send) -> impl ::std::future::Future<Output = ::std::result::Result<u64, Self::Error>> + Send {
+                #update_body
+            }
+            fn delete<'a>(&mut self, e: impl #krate::sqlx::Acquire<'a, Database = #krate::sqlx::Postgres> + Send) -> impl ::std::future::Future<Output = ::std::result::Result<u64, Self::Error>> + Send {
+                #delete_body
+            }
+            fn find<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &Self::FindFilter) -> impl ::std::future::Future<Output = ::std::result::Result<::std::option::Option<Self>, Self::Error>> + Send {
+                #find_body
+            }
+            fn list(p: &#krate::sqlx::PgPool, f: &Self::ListFilter) -> impl ::std::future::Future<Output = ::std::result::Result<Self::Paginate, Self::Error>> + Send {
+                #list_body
+            }
+            fn delete_where<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &Self::ListFilter) -> impl ::std::future::Future<Output = ::std::result::Result<u64, Self::Error>> + Send {
+                Self::delete_where(e, f)
+            }
+            fn update_where<'a>(e: impl #krate::sqlx::PgExecutor<'a>, f: &Self::ListFilter, p: &Self::Patch) -> impl ::std::future::Future<Output = ::std::result::Result<u64, Self::Error>> + Send {
+                Self::update_where(e, f, p)
+            }
+        }
+    }
+}
+
+/// 生成单个可选过滤字段的查询片段，`v` 为字段值的引用
+///
+/// `opt_like` 字段在 SQL 中拼接通配符，避免每次调用都分配参数字符串；空字符串不参与过滤
+fn _filter_push_ts(field: &Ident, like: bool) -> proc_macro2::TokenStream {
+    if like {
+        let sql = format!(" AND {:?} ILIKE '%' || ", field.to_string());
+        quote! {
+            if !::std::convert::AsRef::<str>::as_ref(v).is_empty() {
+                q.push(#sql).push_bind(v).push(" || '%'");
+            }
+        }
+    } else {
+        let sql = format!(" AND {:?} = ", field.to_string());
+        quote! { q.push(#sql).push_bind(v); }
+    }
+}
+
+/// 路径的最后一段是否为指定名称
+fn _path_is(p: &Path, name: &str) -> bool {
+    p.segments.last().map(|s| s.ident == name).unwrap_or(false)
+}
+
+fn _gen_entity_ident(idt: Ident) -> Ident {
+    let mut ss = String::new();
+
+    for (idx, c) in idt.to_string().chars().enumerate() {
+        if idx == 0 {
+            ss.push(c.to_ascii_uppercase());
+            continue;
+        }
+        // TODO
+        // if c == '_' {
+
+        //     continue;
+        // }
+        ss.push(c);
+    }
+
+    Ident::new(&ss, idt.span())
+}
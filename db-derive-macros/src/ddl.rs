@@ -0,0 +1,309 @@
+use quote::quote;
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::db::{DbField, DbMeta};
+
+/// 列定义
+pub(crate) struct Column {
+    pub(crate) name: String,
+    pub(crate) sql_type: String,
+    pub(crate) nullable: bool,
+}
+
+impl Column {
+    /// 无法推断类型且未指定 `sql_type` 时返回 `None`
+    pub(crate) fn from_field(dm: &DbMeta, f: &DbField) -> Option<Self> {
+        let (inner, nullable) = match _generic_inner(&f.ty, "Option") {
+            Some(t) => (t, true),
+            None => (&f.ty, false),
+        };
+        let is_pk = f.name == dm.pk;
+        let sql_type = match &f.sql_type {
+            Some(v) => v.clone(),
+            None => {
+                let sql_type = _pg_type(inner)?;
+                // 由数据库生成的整数主键使用自增类型
+                match (is_pk && f.skip_insert, sql_type.as_str()) {
+                    (true, "SMALLINT") => "SMALLSERIAL".to_string(),
+                    (true, "INTEGER") => "SERIAL".to_string(),
+                    (true, "BIGINT") => "BIGSERIAL".to_string(),
+                    _ => sql_type,
+                }
+            }
+        };
+        Some(Self {
+            name: f.name.to_string(),
+            sql_type,
+            nullable,
+        })
+    }
+}
+
+/// 声明 `#[db(ddl)]` 时生成建表、删表语句
+///
+/// 存在无法推断数据库类型的字段（如自定义的 `sqlx::Type` 枚举）时在该字段上报错，
+/// 需为其指定 `#[db(sql_type = "...")]`
+pub(crate) fn ddl_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !dm.ddl {
+        return quote! {};
+    }
+    let columns = match columns(dm) {
+        Ok(v) => v,
+        Err(e) => return e.to_compile_error(),
+    };
+    let create_sql = create_table_sql(dm, &columns);
+    let drop_sql = format!("DROP TABLE IF EXISTS {:?}", &dm.table);
+    let index_sql = index_sql(dm);
+
+    let krate = &dm.krate;
+    let table = &dm.table;
+    let pk = &dm.pk;
+    let columns = dm.fields.iter().zip(&columns).map(|(f, c)| {
+        let name = &c.name;
+        let sql_type = &c.sql_type;
+        let nullable = c.nullable;
+        let unique = f.unique;
+        let default = match &f.default {
+            Some(v) => quote! { ::std::option::Option::Some(#v) },
+            None => quote! { ::std::option::Option::None },
+        };
+        quote! {
+            #krate::ColumnMeta {
+                name: #name,
+                sql_type: #sql_type,
+                nullable: #nullable,
+                unique: #unique,
+                default: #default,
+            }
+        }
+    });
+
+    quote! {
+        /// 数据表元数据
+        pub fn table_meta() -> &'static #krate::TableMeta {
+            static META: #krate::TableMeta = #krate::TableMeta {
+                table: #table,
+                pk: #pk,
+                columns: &[#(#columns),*],
+                create_sql: #create_sql,
+                index_sql: &[#(#index_sql),*],
+            };
+            &META
+        }
+        /// 建表语句
+        pub fn create_table_sql() -> &'static str {
+            #create_sql
+        }
+        /// 删表语句
+        pub fn drop_table_sql() -> &'static str {
+            #drop_sql
+        }
+        /// 根据 `find`、`list`、`list_opt` 及 `index` 属性生成的索引语句
+        pub fn index_sql() -> Vec<&'static str> {
+            vec![#(#index_sql),*]
+        }
+    }
+}
+
+/// 声明 `#[db(ddl)]` 时，实现 `Table` 以便注册到迁移工具
+pub(crate) fn table_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    // 无法推断类型的错误已由 `ddl_ts` 报告
+    if !dm.ddl || columns(dm).is_err() {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+
+    quote! {
+        impl #krate::Table for #ident {
+            fn table_meta() -> &'static #krate::TableMeta {
+                Self::table_meta()
+            }
+        }
+    }
+}
+
+/// 所有列的定义，无法推断类型的字段各自报错
+fn columns(dm: &DbMeta) -> syn::Result<Vec<Column>> {
+    let mut columns = vec![];
+    let mut err: Option<syn::Error> = None;
+    for f in dm.fields.iter() {
+        match Column::from_field(dm, f) {
+            Some(c) => columns.push(c),
+            None => {
+                let e = syn::Error::new_spanned(
+                    &f.ty,
+                    format!(
+                        "无法推断字段 {} 的数据库类型，请使用 #[db(sql_type = \"...\")] 指定",
+                        f.name
+                    ),
+                );
+                match &mut err {
+                    Some(err) => err.combine(e),
+                    None => err = Some(e),
+                }
+            }
+        }
+    }
+    match err {
+        Some(e) => Err(e),
+        None => Ok(columns),
+    }
+}
+
+pub(crate) fn create_table_sql(dm: &DbMeta, columns: &[Column]) -> String {
+    let columns = dm
+        .fields
+        .iter()
+        .zip(columns)
+        .map(|(f, c)| {
+            let mut ss = format!("{:?} {}", &c.name, &c.sql_type);
+            if f.name == dm.pk {
+                ss.push_str(" PRIMARY KEY");
+            } else if !c.nullable {
+                ss.push_str(" NOT NULL");
+            }
+            if f.unique {
+                ss.push_str(" UNIQUE");
+            }
+            if let Some(v) = &f.default {
+                ss.push_str(" DEFAULT ");
+                ss.push_str(v);
+            }
+            format!("  {}", ss)
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {:?} (\n{}\n)",
+        &dm.table, columns
+    )
+}
+
+/// 生成索引语句
+///
+/// - 主键和 `unique` 字段已有索引，不再重复创建
+/// - 相同 `#[db(index = "...")]` 名称的字段合并为复合索引，顺序与字段声明顺序一致
+/// - `opt_like` 字段使用 `ILIKE '%...%'` 查询，为其创建基于 `pg_trgm` 的 GIN 索引
+pub(crate) fn index_sql(dm: &DbMeta) -> Vec<String> {
+    let mut named: Vec<(String, Vec<String>)> = vec![];
+    let mut single = vec![];
+    let mut trgm = vec![];
+
+    for f in dm.fields.iter() {
+        let col = f.name.to_string();
+        if let Some(idx) = &f.index {
+            match named.iter_mut().find(|(n, _)| n == idx) {
+                Some((_, cols)) => cols.push(col),
+                None => named.push((idx.clone(), vec![col])),
+            }
+            continue;
+        }
+        if f.name == dm.pk || f.unique {
+            continue;
+        }
+        if !(f.find || f.find_opt || f.list || f.list_opt) {
+            continue;
+        }
+        if f.opt_like {
+            trgm.push(col);
+        } else {
+            single.push(col);
+        }
+    }
+
+    let mut ss = vec![];
+    if !trgm.is_empty() {
+        ss.push("CREATE EXTENSION IF NOT EXISTS pg_trgm".to_string());
+    }
+    for col in single {
+        ss.push(format!(
+            "CREATE INDEX IF NOT EXISTS {:?} ON {:?} ({:?})",
+            format!("idx_{}_{}", &dm.table, &col),
+            &dm.table,
+            &col
+        ));
+    }
+    for (name, cols) in named {
+        let cols = cols
+            .iter()
+            .map(|c| format!("{:?}", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        ss.push(format!(
+            "CREATE INDEX IF NOT EXISTS {:?} ON {:?} ({})",
+            &name, &dm.table, cols
+        ));
+    }
+    for col in trgm {
+        ss.push(format!(
+            "CREATE INDEX IF NOT EXISTS {:?} ON {:?} USING GIN ({:?} gin_trgm_ops)",
+            format!("idx_{}_{}_trgm", &dm.table, &col),
+            &dm.table,
+            &col
+        ));
+    }
+    ss
+}
+
+/// Rust 类型对应的 PostgreSQL 类型
+fn _pg_type(ty: &Type) -> Option<String> {
+    if let Type::Reference(r) = ty {
+        return _pg_type(&r.elem);
+    }
+    let name = _type_name(ty)?;
+    let t = match name.as_str() {
+        "bool" => "BOOLEAN",
+        "i8" | "i16" => "SMALLINT",
+        "i32" => "INTEGER",
+        "i64" => "BIGINT",
+        "f32" => "REAL",
+        "f64" => "DOUBLE PRECISION",
+        "String" | "str" => "VARCHAR",
+        "DateTime" => "TIMESTAMPTZ",
+        "NaiveDateTime" | "PrimitiveDateTime" => "TIMESTAMP",
+        "OffsetDateTime" => "TIMESTAMPTZ",
+        "NaiveDate" | "Date" => "DATE",
+        "NaiveTime" | "Time" => "TIME",
+        "Uuid" => "UUID",
+        "Json" | "JsonValue" | "Value" => "JSONB",
+        "Decimal" | "BigDecimal" => "NUMERIC",
+        "IpNetwork" | "IpAddr" => "INET",
+        "Vec" => {
+            let inner = _generic_inner(ty, "Vec")?;
+            if _type_name(inner).as_deref() == Some("u8") {
+                return Some("BYTEA".to_string());
+            }
+            return _pg_type(inner).map(|t| format!("{}[]", t));
+        }
+        _ => return None,
+    };
+    Some(t.to_string())
+}
+
+/// 类型路径最后一段的名称
+pub(crate) fn _type_name(ty: &Type) -> Option<String> {
+    if let Type::Path(p) = ty {
+        return p.path.segments.last().map(|s| s.ident.to_string());
+    }
+    None
+}
+
+/// 形如 `Option<T>` 的类型，返回 `T`
+pub(crate) fn _generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let seg = p.path.segments.last()?;
+    if seg.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(t) => Some(t),
+        _ => None,
+    }
+}
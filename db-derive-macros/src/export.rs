@@ -0,0 +1,50 @@
+use quote::quote;
+use syn::Ident;
+
+use crate::db::DbMeta;
+
+/// 启用 `export` 特性时，生成 CSV / NDJSON 导出方法
+pub(crate) fn export_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "export") {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+    let filter_ident = Ident::new(&format!("{}ListFilter", dm.ident), dm.ident.span());
+    let error_ident = dm.error_ident();
+
+    quote! {
+        /// 将符合条件的记录以 CSV 格式写入 `w`，返回写入的记录数
+        pub async fn export_csv<'a>(e: impl #krate::sqlx::PgExecutor<'a> + 'a, f: &'a #filter_ident, w: &mut impl ::std::io::Write) -> ::std::result::Result<u64, #error_ident> {
+            #krate::export_csv(Self::list_stream(e, f), w).await
+        }
+        /// 将符合条件的记录以 NDJSON 格式写入 `w`，返回写入的记录数
+        pub async fn export_ndjson<'a>(e: impl #krate::sqlx::PgExecutor<'a> + 'a, f: &'a #filter_ident, w: &mut impl ::std::io::Write) -> ::std::result::Result<u64, #error_ident> {
+            #krate::export_ndjson(Self::list_stream(e, f), w).await
+        }
+    }
+}
+
+/// 启用 `export` 特性时，实现 `ExportRow`
+pub(crate) fn export_row_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "export") {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let fields = dm.all_fields();
+    let headers = dm
+        .fields
+        .iter()
+        .map(|f| f.rename.clone().unwrap_or_else(|| f.name.to_string()))
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl #krate::ExportRow for #ident {
+            const HEADERS: &'static [&'static str] = &[#( #headers ),*];
+
+            fn values(&self) -> #krate::serde_json::Result<::std::vec::Vec<#krate::serde_json::Value>> {
+                Ok(vec![ #( #krate::serde_json::to_value(&self.#fields)? ),* ])
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use quote::{format_ident, quote};
+use syn::{Ident, Path};
+
+use crate::db::DbMeta;
+
+/// 生命周期钩子：`#[db(before_insert = "path::fn")]` 等
+///
+/// 钩子签名为 `async fn(&mut T, &mut PgConnection) -> Result<(), E>`，其中 `E: Into<Error>`
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Hooks {
+    pub(crate) before_insert: Option<Path>,
+    pub(crate) after_insert: Option<Path>,
+    pub(crate) before_update: Option<Path>,
+    pub(crate) after_update: Option<Path>,
+    pub(crate) before_delete: Option<Path>,
+    pub(crate) after_delete: Option<Path>,
+}
+
+impl Hooks {
+    /// 按属性名取对应的钩子
+    pub(crate) fn slot_mut(&mut self, name: &str) -> Option<&mut Option<Path>> {
+        match name {
+            "before_insert" => Some(&mut self.before_insert),
+            "after_insert" => Some(&mut self.after_insert),
+            "before_update" => Some(&mut self.before_update),
+            "after_update" => Some(&mut self.after_update),
+            "before_delete" => Some(&mut self.before_delete),
+            "after_delete" => Some(&mut self.after_delete),
+            _ => None,
+        }
+    }
+
+    fn get(&self, op: &str) -> (Option<&Path>, Option<&Path>) {
+        match op {
+            "insert" => (self.before_insert.as_ref(), self.after_insert.as_ref()),
+            "update" => (self.before_update.as_ref(), self.after_update.as_ref()),
+            "delete" => (self.before_delete.as_ref(), self.after_delete.as_ref()),
+            _ => unreachable!(),
+        }
+    }
+
+    /// 操作 `op`（insert / update / delete）是否有钩子
+    pub(crate) fn has(&self, op: &str) -> bool {
+        let (before, after) = self.get(op);
+        before.is_some() || after.is_some()
+    }
+
+    /// 生成的原始方法：有钩子时改名为私有的 `{op}_raw`，由带钩子的同名方法调用
+    pub(crate) fn raw_method(&self, op: &str) -> (proc_macro2::TokenStream, Ident) {
+        if self.has(op) {
+            (quote! {}, format_ident!("{}_raw", op))
+        } else {
+            (quote! { pub }, format_ident!("{}", op))
+        }
+    }
+}
+
+impl DbMeta {
+    /// 操作 `op` 的接收者及执行器参数：有钩子时需要 `&mut self` 以及可开启事务的连接
+    pub(crate) fn hooked_receiver_ts(&self, op: &str, mutable: bool) -> proc_macro2::TokenStream {
+        let krate = &self.krate;
+        let hooked = self.hooks.has(op);
+        let receiver = if mutable || hooked {
+            quote! { &mut self }
+        } else {
+            quote! { &self }
+        };
+        if hooked {
+            quote! { #receiver, e: impl #krate::sqlx::Acquire<'a, Database = #krate::sqlx::Postgres> }
+        } else {
+            quote! { #receiver, e: impl #krate::sqlx::PgExecutor<'a> }
+        }
+    }
+}
+
+/// 生成带钩子的 `insert`、`update`、`delete`
+///
+/// 原始语句与钩子在同一事务中执行（已在事务中时为保存点），任一钩子返回错误都会回滚
+pub(crate) fn hooks_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let error_ident = dm.error_ident();
+    let pk_type = dm.pk_type();
+
+    let methods = [
+        ("insert", quote! { #pk_type }, "插入"),
+        ("update", quote! { u64 }, "按主键更新"),
+        ("delete", quote! { u64 }, "按主键删除"),
+    ]
+    .into_iter()
+    .filter(|(op, _, _)| dm.hooks.has(op))
+    .map(|(op, ret, desc)| {
+        let (before, after) = dm.hooks.get(op);
+        let before = before.map(|p| quote! { #p(self, &mut *tx).await?; });
+        let after = after.map(|p| quote! { #p(self, &mut *tx).await?; });
+        let name = format_ident!("{}", op);
+        let raw = format_ident!("{}_raw", op);
+        let doc = format!("{}，并调用生命周期钩子；钩子返回错误时回滚", desc);
+
+        quote! {
+            #[doc = #doc]
+            pub async fn #name<'a>(&mut self, e: impl #krate::sqlx::Acquire<'a, Database = #krate::sqlx::Postgres>) -> ::std::result::Result<#ret, #error_ident> {
+                let mut tx = e.begin().await?;
+                #before
+                let r = self.#raw(&mut *tx).await?;
+                #after
+                tx.commit().await?;
+                Ok(r)
+            }
+        }
+    });
+
+    quote! {
+        #( #methods )*
+    }
+}
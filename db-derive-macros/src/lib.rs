@@ -0,0 +1,86 @@
+mod aggregate;
+mod db;
+mod ddl;
+mod export;
+mod hook;
+mod lock;
+mod query;
+mod router;
+mod validate;
+
+#[proc_macro_derive(Db, attributes(db))]
+pub fn db_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    let dm = db::parse_db_meta(&ast);
+    let name = &dm.ident;
+
+    let const_ts = db::const_ts(&dm);
+    let insert_ts = db::insert_ts(&dm);
+    let copy_ts = db::copy_ts(&dm);
+    let update_ts = db::update_ts(&dm);
+    let update_one_ts = db::update_one_ts(&dm);
+    let hooks_ts = hook::hooks_ts(&dm);
+    let validate_ts = validate::validate_ts(&dm);
+    let del_ts = db::del_ts(&dm);
+    let error_ts = db::error_ts(&dm);
+
+    let find_by_ts = db::find_by_ts(&dm);
+    let find_ts = db::find_ts(&dm);
+
+    let list_filter_ts = db::list_filter_ts(&dm);
+    let list_ts = db::list_ts(&dm);
+
+    let patch_ts = db::patch_ts(&dm);
+    let where_ts = db::where_ts(&dm);
+
+    let entity_ts = db::entity_ts(&dm);
+
+    let ddl_ts = ddl::ddl_ts(&dm);
+    let table_ts = ddl::table_ts(&dm);
+
+    let column_ts = query::column_ts(&dm);
+    let select_ts = query::select_ts(&dm);
+
+    let lock_ts = lock::lock_ts(&dm);
+
+    let aggregate_ts = aggregate::aggregate_ts(&dm);
+    let facet_ts = aggregate::facet_ts(&dm);
+
+    let export_ts = export::export_ts(&dm);
+    let export_row_ts = export::export_row_ts(&dm);
+
+    let router_ts = router::router_ts(&dm);
+
+    quote::quote! {
+        impl #name {
+            #const_ts
+            #insert_ts
+            #copy_ts
+            #update_ts
+            #update_one_ts
+            #hooks_ts
+            #validate_ts
+            #del_ts
+            #find_ts
+            #list_ts
+            #where_ts
+            #ddl_ts
+            #select_ts
+            #lock_ts
+            #aggregate_ts
+            #facet_ts
+            #export_ts
+        }
+
+        #error_ts
+        #find_by_ts
+        #list_filter_ts
+        #patch_ts
+        #entity_ts
+        #table_ts
+        #router_ts
+        #column_ts
+        #export_row_ts
+    }
+    .into()
+}
@@ -0,0 +1,50 @@
+use quote::quote;
+use syn::Ident;
+
+use crate::db::DbMeta;
+
+/// 生成行锁查询 `find_for_update` 以及任务队列用的 `claim_batch`
+pub(crate) fn lock_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let find_ident = Ident::new(&format!("{}FindFilter", ident), ident.span());
+    let filter_ident = Ident::new(&format!("{}ListFilter", ident), ident.span());
+
+    let fields_str = dm
+        .all_fields()
+        .iter()
+        .map(|f| format!("{:?}", f.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!("SELECT {} FROM {:?} WHERE 1=1", &fields_str, &dm.table);
+    let claim_order = format!(" ORDER BY {:?} LIMIT ", &dm.pk);
+    let find_ret = dm.result_ts(quote! { ::std::option::Option<Self> });
+    let claim_ret = dm.result_ts(quote! { Vec<Self> });
+    let fetch_optional = dm.tail_ts(quote! { q.build_query_as().fetch_optional(tx).await });
+    let fetch_all = dm.tail_ts(quote! { q.build_query_as().fetch_all(tx).await });
+
+    quote! {
+        /// 查找并锁定记录，锁在事务结束时释放
+        pub async fn find_for_update(tx: &mut #krate::sqlx::PgConnection, f: &#find_ident, mode: #krate::LockMode) -> #find_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            Self::push_find_filter(&mut q, f);
+            // 只锁定返回的那一行
+            q.push(" LIMIT 1");
+            q.push(mode.as_sql());
+
+            #fetch_optional
+        }
+
+        /// 按主键顺序领取最多 `n` 条未被其它事务锁定的记录（`FOR UPDATE SKIP LOCKED`）
+        ///
+        /// 多个 worker 并发领取时不会拿到同一条记录，处理完成后应在同一事务中更新或删除
+        pub async fn claim_batch(tx: &mut #krate::sqlx::PgConnection, f: &#filter_ident, n: u32) -> #claim_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            Self::push_list_filter(&mut q, f);
+            q.push(#claim_order).push_bind(i64::from(n));
+            q.push(" FOR UPDATE SKIP LOCKED");
+
+            #fetch_all
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use quote::quote;
+use syn::Ident;
+
+use crate::{db::DbMeta, ddl::_generic_inner};
+
+/// 生成列枚举 `{Name}Column`、类型化的列 `{Name}Columns` 以及基于条件表达式的查询
+pub(crate) fn column_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let column_ident = Ident::new(&format!("{}Column", ident), ident.span());
+    let columns_ident = Ident::new(&format!("{}Columns", ident), ident.span());
+    let cond_ident = dm.cond_ident();
+
+    let fields = dm.all_fields();
+    let fields_str = fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+    let variants = fields.iter().map(_gen_column_ident).collect::<Vec<_>>();
+    // 可空列的取值类型为 `Option` 内部的类型
+    let types = dm
+        .fields
+        .iter()
+        .map(|f| _generic_inner(&f.ty, "Option").unwrap_or(&f.ty).clone())
+        .collect::<Vec<_>>();
+
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum #column_ident {
+            #( #variants, )*
+        }
+        impl #column_ident {
+            pub const ALL: &'static [Self] = &[#( Self::#variants ),*];
+
+            /// 列名
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #( Self::#variants => #fields_str, )*
+                }
+            }
+        }
+
+        pub struct #columns_ident {
+            #( pub #fields: #krate::Col<#ident, #types>, )*
+        }
+
+        /// 组合条件，可用于 `list`、`list_count`、`find`、`delete_where` 等方法
+        pub type #cond_ident = #krate::Expr<#ident>;
+    }
+}
+
+pub(crate) fn select_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let columns_ident = Ident::new(&format!("{}Columns", ident), ident.span());
+
+    let fields = dm.all_fields();
+    let fields_str = fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+    let column_list = fields_str
+        .iter()
+        .map(|f| format!("{:?}", f))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!("SELECT {} FROM {:?} WHERE ", &column_list, &dm.table);
+    let sql_count = format!("SELECT COUNT(*) FROM {:?} WHERE ", &dm.table);
+    let select_ret = dm.result_ts(quote! { Vec<Self> });
+    let count_ret = dm.result_ts(quote! { i64 });
+    let fetch_all = dm.tail_ts(quote! { q.build_query_as().fetch_all(e).await });
+
+    quote! {
+        /// 类型化的列，用于构造查询条件
+        pub fn col() -> #columns_ident {
+            #columns_ident {
+                #( #fields: #krate::Col::new(#fields_str), )*
+            }
+        }
+        /// 按条件表达式查询
+        pub async fn select<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, cond: &#krate::Expr<Self>) -> #select_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql);
+            cond.push_to(&mut q);
+            #fetch_all
+        }
+        /// 按条件表达式统计
+        pub async fn select_count<'a>(e: impl  #krate::sqlx::PgExecutor<'a>, cond: &#krate::Expr<Self>) -> #count_ret {
+            let mut q = #krate::sqlx::QueryBuilder::new(#sql_count);
+            cond.push_to(&mut q);
+            let count: (i64,) = q.build_query_as().fetch_one(e).await?;
+            Ok(count.0)
+        }
+    }
+}
+
+/// 列枚举的变体名，如 `user_id` => `UserId`
+fn _gen_column_ident(idt: &Ident) -> Ident {
+    let mut ss = String::new();
+    let mut upper = true;
+
+    for c in idt.to_string().chars() {
+        if c == '_' {
+            upper = true;
+            continue;
+        }
+        if upper {
+            ss.push(c.to_ascii_uppercase());
+            upper = false;
+            continue;
+        }
+        ss.push(c);
+    }
+
+    Ident::new(&ss, idt.span())
+}
@@ -0,0 +1,125 @@
+use quote::quote;
+use syn::Ident;
+
+use crate::db::{_gen_snake_name, DbMeta};
+
+/// 生成 axum 路由：`fn {name}_router<S>() -> axum::Router<S>`
+pub(crate) fn router_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !dm.router {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+    let ident = &dm.ident;
+    let router_ident = Ident::new(
+        &format!("{}_router", _gen_snake_name(&ident.to_string())),
+        ident.span(),
+    );
+    let filter_ident = Ident::new(&format!("{}ListFilter", ident), ident.span());
+    let paginate_ident = Ident::new(&format!("{}Paginate", ident), ident.span());
+    let patch_ident = dm.patch_ident();
+    let pk = dm.pk_ident();
+    let pk_type = dm.pk_type();
+    // 有钩子时，新增需可变的实体；删除需先查出实体再删除，以便调用钩子
+    let create_binding = if dm.hooks.has("insert") {
+        quote! { mut m }
+    } else {
+        quote! { m }
+    };
+    // 有更新钩子时，部分更新先查出实体，写入变更后通过 `update_one` 保存
+    let patch_body = if dm.hooks.has("update") {
+        quote! {
+            let mut m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+            patch.apply(&mut m);
+            m.update_one(&p).await?;
+            Ok(Json(m))
+        }
+    } else {
+        quote! {
+            #ident::patch(&p, &id, &patch).await?;
+            let m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+            Ok(Json(m))
+        }
+    };
+    let delete_body = if dm.hooks.has("delete") {
+        quote! {
+            let mut m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+            m.delete_one(&p).await?;
+            Ok(#krate::axum::http::StatusCode::NO_CONTENT)
+        }
+    } else {
+        quote! {
+            match #ident::delete_by_pk(&p, &id).await? {
+                0 => Err(#krate::Error::NotFound),
+                _ => Ok(#krate::axum::http::StatusCode::NO_CONTENT),
+            }
+        }
+    };
+
+    quote! {
+        pub fn #router_ident<S>() -> #krate::axum::Router<S>
+        where
+            S: ::std::clone::Clone + ::std::marker::Send + ::std::marker::Sync + 'static,
+            #krate::sqlx::PgPool: #krate::axum::extract::FromRef<S>,
+        {
+            use #krate::axum::extract::{Json, Path, Query, State};
+
+            type Result<T> = ::std::result::Result<T, #krate::Error>;
+
+            async fn list(
+                State(p): State<#krate::sqlx::PgPool>,
+                Query(f): Query<#filter_ident>,
+            ) -> Result<Json<#paginate_ident>> {
+                Ok(Json(#ident::list(&p, &f).await?))
+            }
+            async fn find(
+                State(p): State<#krate::sqlx::PgPool>,
+                Path(id): Path<#pk_type>,
+            ) -> Result<Json<#ident>> {
+                let m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+                Ok(Json(m))
+            }
+            async fn create(
+                State(p): State<#krate::sqlx::PgPool>,
+                Json(#create_binding): Json<#ident>,
+            ) -> Result<(#krate::axum::http::StatusCode, Json<#ident>)> {
+                let id = m.insert(&p).await?;
+                let m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+                Ok((#krate::axum::http::StatusCode::CREATED, Json(m)))
+            }
+            async fn update(
+                State(p): State<#krate::sqlx::PgPool>,
+                Path(id): Path<#pk_type>,
+                Json(mut m): Json<#ident>,
+            ) -> Result<Json<#ident>> {
+                m.#pk = id;
+                m.update_one(&p).await?;
+                // 重新查询，返回数据库中的值，而非请求体中未写入的字段
+                let m = #ident::find_by_pk(&p, &id).await?.ok_or(#krate::Error::NotFound)?;
+                Ok(Json(m))
+            }
+            async fn patch(
+                State(p): State<#krate::sqlx::PgPool>,
+                Path(id): Path<#pk_type>,
+                Json(patch): Json<#patch_ident>,
+            ) -> Result<Json<#ident>> {
+                #patch_body
+            }
+            async fn delete(
+                State(p): State<#krate::sqlx::PgPool>,
+                Path(id): Path<#pk_type>,
+            ) -> Result<#krate::axum::http::StatusCode> {
+                #delete_body
+            }
+
+            #krate::axum::Router::new()
+                .route("/", #krate::axum::routing::get(list).post(create))
+                .route(
+                    "/:id",
+                    #krate::axum::routing::get(find)
+                        .put(update)
+                        .patch(patch)
+                        .delete(delete),
+                )
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use quote::{quote, ToTokens};
+use syn::{meta::ParseNestedMeta, Expr, ExprLit, ExprUnary, Lit, LitFloat, LitInt, Type, UnOp};
+
+use crate::{
+    db::{DbField, DbMeta},
+    ddl::{_generic_inner, _type_name},
+};
+
+/// 字段校验规则：`#[db(validate(len(min = 1, max = 200), email, range(min = 0)))]`
+#[derive(Debug, Clone)]
+pub(crate) enum Rule {
+    Len {
+        min: Option<LitInt>,
+        max: Option<LitInt>,
+    },
+    Email,
+    Range {
+        min: Option<Box<Expr>>,
+        max: Option<Box<Expr>>,
+    },
+}
+
+/// 解析 `validate(...)` 中的规则
+pub(crate) fn parse_rules(mt: &ParseNestedMeta, rules: &mut Vec<Rule>) -> syn::Result<()> {
+    mt.parse_nested_meta(|r| {
+        if r.path.is_ident("email") {
+            rules.push(Rule::Email);
+            return Ok(());
+        }
+        if r.path.is_ident("len") {
+            let (mut min, mut max) = (None, None);
+            r.parse_nested_meta(|b| {
+                if b.path.is_ident("min") {
+                    min = Some(b.value()?.parse()?);
+                } else if b.path.is_ident("max") {
+                    max = Some(b.value()?.parse()?);
+                } else {
+                    return Err(b.error("len 仅支持 min、max"));
+                }
+                Ok(())
+            })?;
+            rules.push(Rule::Len { min, max });
+            return Ok(());
+        }
+        if r.path.is_ident("range") {
+            let (mut min, mut max) = (None, None);
+            r.parse_nested_meta(|b| {
+                if b.path.is_ident("min") {
+                    min = Some(Box::new(b.value()?.parse()?));
+                } else if b.path.is_ident("max") {
+                    max = Some(Box::new(b.value()?.parse()?));
+                } else {
+                    return Err(b.error("range 仅支持 min、max"));
+                }
+                Ok(())
+            })?;
+            rules.push(Rule::Range { min, max });
+            return Ok(());
+        }
+        Err(r.error("未知的校验规则，支持 len、email、range"))
+    })
+}
+
+impl DbMeta {
+    /// 是否有字段声明了校验规则
+    pub(crate) fn has_validation(&self) -> bool {
+        self.fields.iter().any(|f| !f.validate.is_empty())
+    }
+
+    /// `{Name}Patch` 中是否有需要校验的字段
+    pub(crate) fn has_patch_validation(&self) -> bool {
+        let fields = self.update_fileds();
+        self.fields
+            .iter()
+            .any(|f| !f.validate.is_empty() && fields.contains(&f.name))
+    }
+
+    /// 写方法的返回类型及写入前的校验语句：有校验规则时返回 `{Name}Error`
+    pub(crate) fn validated_write_ts(
+        &self,
+        ok: proc_macro2::TokenStream,
+    ) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        if self.has_validation() {
+            let error_ident = self.error_ident();
+            (
+                quote! { ::std::result::Result<#ok, #error_ident> },
+                quote! { self.validate()?; },
+            )
+        } else {
+            (self.result_ts(ok), quote! {})
+        }
+    }
+}
+
+/// 生成 `validate()`，在 `insert`、`update`、`copy_in` 发送 SQL 前调用
+pub(crate) fn validate_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !dm.has_validation() {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+
+    let checks = dm
+        .fields
+        .iter()
+        .filter(|f| !f.validate.is_empty())
+        .map(|f| {
+            let name = &f.name;
+            let check = _field_check_ts(dm, f);
+            quote! {
+                let v = &self.#name;
+                #check
+            }
+        });
+
+    quote! {
+        /// 按 `#[db(validate(...))]` 规则校验字段
+        pub fn validate(&self) -> ::std::result::Result<(), #krate::ValidationErrors> {
+            let mut errs = #krate::ValidationErrors::new();
+            #( { #checks } )*
+            errs.into_result()
+        }
+    }
+}
+
+/// 生成 `{Name}Patch::validate()`，只校验 `Some` 的字段，在 `patch`、`update_where` 发送 SQL 前调用
+pub(crate) fn patch_validate_ts(dm: &DbMeta) -> proc_macro2::TokenStream {
+    if !dm.has_patch_validation() {
+        return quote! {};
+    }
+    let krate = &dm.krate;
+    let fields = dm.update_fileds();
+
+    let checks = dm
+        .fields
+        .iter()
+        .filter(|f| !f.validate.is_empty() && fields.contains(&f.name))
+        .map(|f| {
+            let name = &f.name;
+            let check = _field_check_ts(dm, f);
+            quote! {
+                if let Some(v) = &self.#name {
+                    #check
+                }
+            }
+        });
+
+    quote! {
+        /// 按 `#[db(validate(...))]` 规则校验 `Some` 的字段
+        pub fn validate(&self) -> ::std::result::Result<(), #krate::ValidationErrors> {
+            let mut errs = #krate::ValidationErrors::new();
+            #( #checks )*
+            errs.into_result()
+        }
+    }
+}
+
+/// 单个字段的校验语句，`v` 为字段值的引用
+fn _field_check_ts(dm: &DbMeta, f: &DbField) -> proc_macro2::TokenStream {
+    let krate = &dm.krate;
+    let name_str = f.name.to_string();
+    let inner = _generic_inner(&f.ty, "Option");
+    let ty = inner.unwrap_or(&f.ty);
+
+    let rules = f.validate.iter().map(|r| {
+        let check = match r {
+            Rule::Len { min, max } => {
+                let (min, max) = (_opt_ts(min), _opt_ts(max));
+                quote! { #krate::validate::len(v, #min, #max) }
+            }
+            Rule::Email => quote! { #krate::validate::email(v) },
+            Rule::Range { min, max } => {
+                let (min, max) = (_bound_ts(min, ty), _bound_ts(max, ty));
+                quote! { #krate::validate::range(v, #min, #max) }
+            }
+        };
+        quote! {
+            if let Some(msg) = #check {
+                errs.add(#name_str, msg);
+            }
+        }
+    });
+    // 可空字段为 `None` 时不校验
+    if inner.is_some() {
+        quote! {
+            if let Some(v) = v {
+                #( #rules )*
+            }
+        }
+    } else {
+        quote! { #( #rules )* }
+    }
+}
+
+fn _opt_ts(v: &Option<impl ToTokens>) -> proc_macro2::TokenStream {
+    match v {
+        Some(v) => quote! { ::std::option::Option::Some(#v) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// `range` 的边界，浮点字段的整数字面量转为浮点字面量，如 `range(min = 0)`
+fn _bound_ts(v: &Option<Box<Expr>>, ty: &Type) -> proc_macro2::TokenStream {
+    match v {
+        Some(v) if matches!(_type_name(ty).as_deref(), Some("f32" | "f64")) => {
+            _opt_ts(&Some(_int_to_float(v)))
+        }
+        v => _opt_ts(v),
+    }
+}
+
+fn _int_to_float(e: &Expr) -> proc_macro2::TokenStream {
+    match e {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => {
+            let f = LitFloat::new(&format!("{}.0", i.base10_digits()), i.span());
+            quote! { #f }
+        }
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => {
+            let inner = _int_to_float(expr);
+            quote! { -#inner }
+        }
+        e => quote! { #e },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn bound(e: Expr, ty: Type) -> String {
+        _bound_ts(&Some(Box::new(e)), &ty).to_string()
+    }
+
+    #[test]
+    fn float_bound() {
+        let some =
+            |v: proc_macro2::TokenStream| quote! { ::std::option::Option::Some(#v) }.to_string();
+        assert_eq!(
+            bound(parse_quote!(0), parse_quote!(f64)),
+            some(quote! { 0.0 })
+        );
+        assert_eq!(
+            bound(parse_quote!(-1), parse_quote!(f32)),
+            some(quote! { -1.0 })
+        );
+        assert_eq!(
+            bound(parse_quote!(0.5), parse_quote!(f64)),
+            some(quote! { 0.5 })
+        );
+        assert_eq!(
+            bound(parse_quote!(0), parse_quote!(i32)),
+            some(quote! { 0 })
+        );
+        assert_eq!(
+            _bound_ts(&None, &parse_quote!(f64)).to_string(),
+            quote! { ::std::option::Option::None }.to_string()
+        );
+    }
+}
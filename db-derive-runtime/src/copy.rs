@@ -0,0 +1,114 @@
+use std::future::poll_fn;
+use std::io::Write;
+
+use sqlx::encode::IsNull;
+use sqlx::postgres::PgArgumentBuffer;
+use sqlx::{Encode, PgConnection, Postgres};
+
+use crate::Error;
+
+/// 二进制 COPY 数据的文件头：签名、标志位、扩展区长度
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+/// 每次发送给数据库的数据块大小
+pub const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// `copy_out` 的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+    /// Postgres 二进制格式
+    #[default]
+    Binary,
+    /// 带表头的 CSV
+    Csv,
+}
+
+impl CopyFormat {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Binary => "(FORMAT binary)",
+            Self::Csv => "(FORMAT csv, HEADER true)",
+        }
+    }
+}
+
+/// 按 Postgres 二进制 COPY 格式编码记录
+///
+/// 字段使用 sqlx 的 `Encode` 编码，与 `insert` 绑定参数时一致
+pub struct CopyEncoder {
+    buf: Vec<u8>,
+    arg: PgArgumentBuffer,
+}
+
+impl Default for CopyEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopyEncoder {
+    pub fn new() -> Self {
+        Self {
+            buf: HEADER.to_vec(),
+            arg: PgArgumentBuffer::default(),
+        }
+    }
+
+    /// 开始一行，`fields` 为该行的字段数
+    pub fn row(&mut self, fields: i16) {
+        self.buf.extend_from_slice(&fields.to_be_bytes());
+    }
+
+    /// 追加一个字段
+    pub fn field<T>(&mut self, v: &T)
+    where
+        T: for<'q> Encode<'q, Postgres>,
+    {
+        self.arg.clear();
+        match v.encode_by_ref(&mut self.arg) {
+            IsNull::Yes => self.buf.extend_from_slice(&(-1i32).to_be_bytes()),
+            IsNull::No => {
+                self.buf
+                    .extend_from_slice(&(self.arg.len() as i32).to_be_bytes());
+                self.buf.extend_from_slice(&self.arg);
+            }
+        }
+    }
+
+    /// 已编码的数据长度
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// 取出已编码的数据
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
+
+    /// 写入结束标记并取出剩余数据
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buf.extend_from_slice(&(-1i16).to_be_bytes());
+        self.buf
+    }
+}
+
+/// 执行 `COPY ... TO STDOUT`，将数据原样写入 `w`，返回写入的字节数
+pub async fn copy_out(
+    conn: &mut PgConnection,
+    sql: &str,
+    w: &mut impl Write,
+) -> Result<u64, Error> {
+    let mut rows = conn.copy_out_raw(sql).await?;
+
+    let mut n = 0;
+    while let Some(chunk) = poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+        let chunk = chunk?;
+        w.write_all(&chunk)?;
+        n += chunk.len() as u64;
+    }
+    w.flush()?;
+    Ok(n)
+}
@@ -0,0 +1,65 @@
+use std::future::Future;
+
+/// 实体：由 `#[derive(Db)]` 自动实现，用于编写泛型的仓储/服务代码
+pub trait Entity: Sized + Send + Sync {
+    /// 数据表名
+    const TABLE: &'static str;
+
+    /// 主键类型
+    type Pk: Send;
+    /// 单条查询的过滤条件，即 `{Name}FindFilter`
+    type FindFilter: Send + Sync;
+    /// 列表查询的过滤条件，即 `{Name}ListFilter`
+    type ListFilter: Send + Sync;
+    /// 部分更新，即 `{Name}Patch`
+    type Patch: Send + Sync;
+    /// 分页结果，即 `{Name}Paginate`
+    type Paginate: Send;
+    /// 错误类型，即 `{Name}Error`
+    type Error: std::error::Error + From<sqlx::Error> + Send;
+
+    /// 插入
+    ///
+    /// 写操作接受可获取连接的参数（连接池、连接或事务），以便在同一连接上调用生命周期钩子
+    fn insert<'a>(
+        &mut self,
+        e: impl sqlx::Acquire<'a, Database = sqlx::Postgres> + Send,
+    ) -> impl Future<Output = Result<Self::Pk, Self::Error>> + Send;
+
+    /// 按主键更新
+    fn update<'a>(
+        &mut self,
+        e: impl sqlx::Acquire<'a, Database = sqlx::Postgres> + Send,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+
+    /// 按主键删除
+    fn delete<'a>(
+        &mut self,
+        e: impl sqlx::Acquire<'a, Database = sqlx::Postgres> + Send,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+
+    /// 查找单条记录
+    fn find<'a>(
+        e: impl sqlx::PgExecutor<'a>,
+        f: &Self::FindFilter,
+    ) -> impl Future<Output = Result<Option<Self>, Self::Error>> + Send;
+
+    /// 分页列表
+    fn list(
+        p: &sqlx::PgPool,
+        f: &Self::ListFilter,
+    ) -> impl Future<Output = Result<Self::Paginate, Self::Error>> + Send;
+
+    /// 按过滤条件批量删除
+    fn delete_where<'a>(
+        e: impl sqlx::PgExecutor<'a>,
+        f: &Self::ListFilter,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+
+    /// 按过滤条件批量更新
+    fn update_where<'a>(
+        e: impl sqlx::PgExecutor<'a>,
+        f: &Self::ListFilter,
+        p: &Self::Patch,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+}
@@ -0,0 +1,244 @@
+use crate::ValidationErrors;
+
+/// 生成方法返回的错误
+#[derive(Debug)]
+pub enum Error {
+    Sqlx(sqlx::Error),
+    /// 乐观锁版本号不匹配
+    StaleObject,
+    /// 记录不存在
+    NotFound,
+    /// 预期影响1行，实际影响了多行
+    TooManyRows(u64),
+    /// 批量操作的过滤条件为空
+    EmptyFilter,
+    /// 导出时写入失败
+    Io(std::io::Error),
+    /// 被生命周期钩子中止
+    Aborted(String),
+    /// 写入前字段校验失败
+    Validation(ValidationErrors),
+    /// 违反唯一约束（SQLSTATE 23505）
+    UniqueViolation {
+        constraint: Option<String>,
+        /// 约束涉及的列，多列时以 `, ` 分隔
+        column: Option<String>,
+    },
+    /// 违反外键约束（SQLSTATE 23503）
+    ForeignKeyViolation {
+        constraint: Option<String>,
+    },
+    /// 违反检查约束（SQLSTATE 23514）
+    CheckViolation {
+        constraint: Option<String>,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sqlx(e) => std::fmt::Display::fmt(e, f),
+            Self::StaleObject => f.write_str("数据已被修改，版本号不匹配"),
+            Self::NotFound => f.write_str("记录不存在"),
+            Self::TooManyRows(n) => write!(f, "预期影响1行，实际影响{}行", n),
+            Self::EmptyFilter => f.write_str("过滤条件为空，拒绝执行"),
+            Self::Io(e) => std::fmt::Display::fmt(e, f),
+            Self::Aborted(msg) => write!(f, "操作已中止：{}", msg),
+            Self::Validation(e) => std::fmt::Display::fmt(e, f),
+            Self::UniqueViolation { column, .. } => match column {
+                Some(c) => write!(f, "{} 已存在", c),
+                None => f.write_str("记录已存在"),
+            },
+            Self::ForeignKeyViolation { .. } => f.write_str("关联的记录不存在或仍被引用"),
+            Self::CheckViolation { constraint } => match constraint {
+                Some(c) => write!(f, "不满足约束 {}", c),
+                None => f.write_str("不满足检查约束"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Sqlx(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Validation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 按 SQLSTATE 将数据库错误映射为对应的约束错误，`RowNotFound` 映射为 `NotFound`
+impl From<sqlx::Error> for Error {
+    fn from(e: sqlx::Error) -> Self {
+        let db = match &e {
+            sqlx::Error::RowNotFound => return Self::NotFound,
+            sqlx::Error::Database(db) => db,
+            _ => return Self::Sqlx(e),
+        };
+        let constraint = db.constraint().map(ToString::to_string);
+        match db.code().as_deref() {
+            Some("23505") => Self::UniqueViolation {
+                constraint,
+                column: unique_column(db.as_ref()),
+            },
+            Some("23503") => Self::ForeignKeyViolation { constraint },
+            Some("23514") => Self::CheckViolation { constraint },
+            _ => Self::Sqlx(e),
+        }
+    }
+}
+
+/// 唯一约束涉及的列，多列时以 `, ` 分隔
+///
+/// 依次尝试：错误中的列名、形如 `Key (a, b)=(...) already exists.` 的详情、约束名。
+/// 详情随 `lc_messages` 翻译，仅在英文环境下可解析；约束名只在能确定为单列时使用
+fn unique_column(db: &dyn sqlx::error::DatabaseError) -> Option<String> {
+    let pg = db.try_downcast_ref::<sqlx::postgres::PgDatabaseError>();
+    if let Some(c) = pg.and_then(|e| e.column()) {
+        return Some(c.to_string());
+    }
+    if let Some(c) = pg.and_then(|e| e.detail()).and_then(detail_columns) {
+        return Some(c);
+    }
+    constraint_column(db.table()?, db.constraint()?)
+}
+
+/// 从 `Key (a, b)=(...)` 形式的详情中取出列名
+fn detail_columns(detail: &str) -> Option<String> {
+    let start = detail.find("Key (")? + "Key (".len();
+    let end = start + detail[start..].find(")=")?;
+    Some(detail[start..end].to_string())
+}
+
+/// 列级 `UNIQUE` 默认命名为 `{table}_{column}_key`
+///
+/// 多列约束的列名同样以 `_` 连接，含 `_` 时无法区分单列与多列，不作推断
+fn constraint_column(table: &str, constraint: &str) -> Option<String> {
+    let c = constraint
+        .strip_prefix(table)?
+        .strip_prefix('_')?
+        .strip_suffix("_key")?;
+    (!c.is_empty() && !c.contains('_')).then(|| c.to_string())
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ValidationErrors> for Error {
+    fn from(e: ValidationErrors) -> Self {
+        Self::Validation(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    /// 只带 SQLSTATE、表名和约束名的数据库错误
+    #[derive(Debug)]
+    struct DbErr {
+        code: &'static str,
+        table: Option<&'static str>,
+        constraint: Option<&'static str>,
+    }
+
+    impl std::fmt::Display for DbErr {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.code)
+        }
+    }
+
+    impl std::error::Error for DbErr {}
+
+    impl sqlx::error::DatabaseError for DbErr {
+        fn message(&self) -> &str {
+            self.code
+        }
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+        fn table(&self) -> Option<&str> {
+            self.table
+        }
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn db_err(code: &'static str, constraint: &'static str) -> Error {
+        sqlx::Error::Database(Box::new(DbErr {
+            code,
+            table: Some("users"),
+            constraint: Some(constraint),
+        }))
+        .into()
+    }
+
+    #[test]
+    fn sqlstate() {
+        assert!(matches!(
+            db_err("23505", "users_email_key"),
+            Error::UniqueViolation { constraint: Some(c), column: Some(col) }
+                if c == "users_email_key" && col == "email"
+        ));
+        assert!(matches!(
+            db_err("23503", "users_team_id_fkey"),
+            Error::ForeignKeyViolation { constraint: Some(c) } if c == "users_team_id_fkey"
+        ));
+        assert!(matches!(
+            db_err("23514", "users_age_check"),
+            Error::CheckViolation { constraint: Some(c) } if c == "users_age_check"
+        ));
+        assert!(matches!(db_err("42P01", "users_pkey"), Error::Sqlx(_)));
+        assert!(matches!(
+            Error::from(sqlx::Error::RowNotFound),
+            Error::NotFound
+        ));
+    }
+
+    #[test]
+    fn detail() {
+        let d = "Key (first_name, last_name)=(a, b) already exists.";
+        assert_eq!(detail_columns(d).as_deref(), Some("first_name, last_name"));
+        let d = "Key (email)=(a@b.c) already exists.";
+        assert_eq!(detail_columns(d).as_deref(), Some("email"));
+        assert_eq!(detail_columns("键值\"(email)=(a@b.c)\" 已经存在"), None);
+    }
+
+    #[test]
+    fn constraint_name() {
+        assert_eq!(
+            constraint_column("users", "users_email_key").as_deref(),
+            Some("email")
+        );
+        assert_eq!(
+            constraint_column("users", "users_first_name_last_name_key"),
+            None
+        );
+        assert_eq!(constraint_column("users", "uniq_email"), None);
+        assert_eq!(constraint_column("users", "users__key"), None);
+        assert!(matches!(
+            db_err("23505", "users_first_name_last_name_key"),
+            Error::UniqueViolation { column: None, .. }
+        ));
+    }
+}
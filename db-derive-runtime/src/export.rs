@@ -0,0 +1,95 @@
+use std::future::poll_fn;
+use std::io::Write;
+use std::pin::pin;
+
+use futures_core::Stream;
+use serde_json::Value;
+
+use crate::Error;
+
+/// 可导出的记录，由 `#[derive(Db)]` 在启用 `export` 特性时实现
+pub trait ExportRow {
+    /// 表头，默认为列名，可通过 `#[db(rename = "...")]` 修改
+    const HEADERS: &'static [&'static str];
+
+    /// 与表头一一对应的值
+    fn values(&self) -> serde_json::Result<Vec<Value>>;
+}
+
+/// 将记录流以 CSV 格式写入 `w`，首行为表头，返回写入的记录数
+pub async fn export_csv<R, E, S, W>(rows: S, w: &mut W) -> Result<u64, Error>
+where
+    R: ExportRow,
+    Error: From<E>,
+    S: Stream<Item = Result<R, E>>,
+    W: Write,
+{
+    let mut rows = pin!(rows);
+    write_csv_line(w, R::HEADERS.iter().copied())?;
+
+    let mut n = 0;
+    while let Some(row) = poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+        let values = row?.values().map_err(std::io::Error::from)?;
+        write_csv_line(w, values.iter().map(csv_cell))?;
+        n += 1;
+    }
+    w.flush()?;
+    Ok(n)
+}
+
+/// 将记录流以 NDJSON 格式写入 `w`，每行一个以表头为键的对象，返回写入的记录数
+pub async fn export_ndjson<R, E, S, W>(rows: S, w: &mut W) -> Result<u64, Error>
+where
+    R: ExportRow,
+    Error: From<E>,
+    S: Stream<Item = Result<R, E>>,
+    W: Write,
+{
+    let mut rows = pin!(rows);
+
+    let mut n = 0;
+    while let Some(row) = poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+        let values = row?.values().map_err(std::io::Error::from)?;
+        // 手动拼接对象，保持列的顺序
+        w.write_all(b"{")?;
+        for (idx, (k, v)) in R::HEADERS.iter().zip(&values).enumerate() {
+            if idx > 0 {
+                w.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut *w, k).map_err(std::io::Error::from)?;
+            w.write_all(b":")?;
+            serde_json::to_writer(&mut *w, v).map_err(std::io::Error::from)?;
+        }
+        w.write_all(b"}\n")?;
+        n += 1;
+    }
+    w.flush()?;
+    Ok(n)
+}
+
+/// CSV 单元格的文本：`null` 为空，字符串不带引号，其余为 JSON 文本
+fn csv_cell(v: &Value) -> std::borrow::Cow<'_, str> {
+    match v {
+        Value::Null => "".into(),
+        Value::String(s) => s.as_str().into(),
+        v => v.to_string().into(),
+    }
+}
+
+fn write_csv_line<'a, W: Write>(
+    w: &mut W,
+    cells: impl Iterator<Item = impl AsRef<str> + 'a>,
+) -> std::io::Result<()> {
+    for (idx, cell) in cells.enumerate() {
+        if idx > 0 {
+            w.write_all(b",")?;
+        }
+        let cell = cell.as_ref();
+        if cell.contains([',', '"', '\r', '\n']) {
+            write!(w, "\"{}\"", cell.replace('"', "\"\""))?;
+        } else {
+            w.write_all(cell.as_bytes())?;
+        }
+    }
+    w.write_all(b"\r\n")
+}
@@ -0,0 +1,35 @@
+mod copy;
+mod entity;
+mod error;
+#[cfg(feature = "export")]
+mod export;
+mod lock;
+mod meta;
+mod paginate;
+mod query;
+#[cfg(feature = "axum")]
+mod response;
+pub mod validate;
+
+pub use copy::{copy_out, CopyEncoder, CopyFormat, COPY_CHUNK_SIZE};
+pub use entity::Entity;
+pub use error::Error;
+#[cfg(feature = "export")]
+pub use export::{export_csv, export_ndjson, ExportRow};
+pub use lock::LockMode;
+pub use meta::{ColumnMeta, Table, TableMeta};
+pub use paginate::{Paginate, PaginateReq, SortDir, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+pub use query::{Col, Expr, Value};
+#[cfg(feature = "axum")]
+pub use response::ErrorBody;
+pub use validate::ValidationErrors;
+
+pub use async_stream;
+#[cfg(feature = "axum")]
+pub use axum;
+pub use futures_core::Stream;
+pub use serde;
+#[cfg(feature = "export")]
+pub use serde_json;
+#[cfg(feature = "utoipa")]
+pub use utoipa;
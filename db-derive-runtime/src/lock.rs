@@ -0,0 +1,24 @@
+/// `find_for_update` 的行锁模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockMode {
+    /// `FOR UPDATE`
+    #[default]
+    Update,
+    /// `FOR NO KEY UPDATE`，不阻塞引用该行的外键检查
+    NoKeyUpdate,
+    /// `FOR UPDATE NOWAIT`，行已被锁定时立即返回错误
+    UpdateNowait,
+    /// `FOR NO KEY UPDATE NOWAIT`
+    NoKeyUpdateNowait,
+}
+
+impl LockMode {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Update => " FOR UPDATE",
+            Self::NoKeyUpdate => " FOR NO KEY UPDATE",
+            Self::UpdateNowait => " FOR UPDATE NOWAIT",
+            Self::NoKeyUpdateNowait => " FOR NO KEY UPDATE NOWAIT",
+        }
+    }
+}
@@ -0,0 +1,41 @@
+/// 数据表元数据，由 `#[derive(Db)]` 生成，供迁移工具等外部代码使用
+#[derive(Debug)]
+pub struct TableMeta {
+    /// 表名
+    pub table: &'static str,
+    /// 主键
+    pub pk: &'static str,
+    /// 所有列
+    pub columns: &'static [ColumnMeta],
+    /// 建表语句
+    pub create_sql: &'static str,
+    /// 索引语句
+    pub index_sql: &'static [&'static str],
+}
+
+impl TableMeta {
+    pub fn column(&self, name: &str) -> Option<&ColumnMeta> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+/// 可生成建表语句的实体，由 `#[derive(Db)]` 实现，用于向迁移工具注册
+pub trait Table {
+    /// 数据表元数据
+    fn table_meta() -> &'static TableMeta;
+}
+
+/// 列元数据
+#[derive(Debug)]
+pub struct ColumnMeta {
+    /// 列名
+    pub name: &'static str,
+    /// 建表语句中使用的类型，如 `SERIAL`、`VARCHAR`
+    pub sql_type: &'static str,
+    /// 是否允许为 `NULL`
+    pub nullable: bool,
+    /// 是否唯一
+    pub unique: bool,
+    /// 默认值表达式
+    pub default: Option<&'static str>,
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+/// 默认分页大小
+pub const DEFAULT_PAGE_SIZE: u32 = 30;
+/// 默认的最大分页大小，可通过 `#[db(max_page_size = ...)]` 修改
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// 分页请求，页码从 0 开始
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "utoipa", into_params(parameter_in = Query))]
+#[cfg_attr(feature = "camel-case", serde(rename_all = "camelCase"))]
+#[serde(default)]
+pub struct PaginateReq {
+    /// 页码，从 0 开始
+    #[serde(deserialize_with = "de_u32")]
+    pub page: u32,
+    /// 分页大小
+    #[serde(deserialize_with = "de_u32")]
+    pub page_size: u32,
+}
+
+impl Default for PaginateReq {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl PaginateReq {
+    pub fn new(page: u32) -> Self {
+        Self {
+            page,
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+    /// 将分页大小限制在 `1..=max` 之间
+    pub fn clamp(&self, max: u32) -> Self {
+        Self {
+            page: self.page,
+            page_size: self.page_size.clamp(1, max.max(1)),
+        }
+    }
+    pub fn limit(&self) -> i64 {
+        self.page_size as i64
+    }
+    pub fn offset(&self) -> i64 {
+        self.page as i64 * self.page_size as i64
+    }
+}
+
+/// 分页结果
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "camel-case", serde(rename_all = "camelCase"))]
+pub struct Paginate<T> {
+    /// 总记录数
+    pub total: u32,
+    /// 总页数
+    pub total_page: u32,
+    /// 当前页码
+    pub page: u32,
+    /// 分页大小
+    pub page_size: u32,
+    /// 当前页数据
+    pub data: Vec<T>,
+}
+
+impl<T> Paginate<T> {
+    pub fn new(total: u32, page: u32, page_size: u32, data: Vec<T>) -> Self {
+        let total_page = f64::ceil(total as f64 / page_size as f64) as u32;
+        Self {
+            total,
+            page,
+            total_page,
+            page_size,
+            data,
+        }
+    }
+    pub fn quick(total: i64, p: &PaginateReq, data: Vec<T>) -> Self {
+        Self::new(total as u32, p.page, p.page_size, data)
+    }
+    /// 转换每条数据，如将实体转换为 DTO
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Paginate<U> {
+        Paginate {
+            total: self.total,
+            total_page: self.total_page,
+            page: self.page,
+            page_size: self.page_size,
+            data: self.data.into_iter().map(f).collect(),
+        }
+    }
+    /// 是否有上一页
+    pub fn has_prev(&self) -> bool {
+        self.page > 0
+    }
+    /// 是否有下一页
+    pub fn has_next(&self) -> bool {
+        self.page.saturating_add(1) < self.total_page
+    }
+}
+
+/// 排序方向
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortDir {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDir {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// 同时接受数字和字符串形式的 `u32`。
+///
+/// 过滤条件通过 `#[serde(flatten)]` 平铺分页参数时，查询字符串中的值都以字符串的形式传入。
+fn de_u32<'de, D>(d: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct V;
+
+    impl serde::de::Visitor<'_> for V {
+        type Value = u32;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("u32 或数字字符串")
+        }
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<u32, E> {
+            u32::try_from(v).map_err(E::custom)
+        }
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<u32, E> {
+            u32::try_from(v).map_err(E::custom)
+        }
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<u32, E> {
+            v.parse().map_err(E::custom)
+        }
+    }
+
+    d.deserialize_any(V)
+}
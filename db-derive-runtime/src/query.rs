@@ -0,0 +1,295 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+
+/// 可绑定到查询中的值
+pub trait Value: Send + Sync {
+    fn push_bind<'q>(&'q self, q: &mut QueryBuilder<'q, Postgres>);
+}
+
+impl<T> Value for T
+where
+    T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync,
+{
+    fn push_bind<'q>(&'q self, q: &mut QueryBuilder<'q, Postgres>) {
+        q.push_bind(self);
+    }
+}
+
+/// 实体 `E` 中类型为 `T` 的列，由 `{Name}::col()` 生成
+pub struct Col<E, T> {
+    name: &'static str,
+    _p: PhantomData<fn() -> (E, T)>,
+}
+
+impl<E, T> Clone for Col<E, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E, T> Copy for Col<E, T> {}
+
+impl<E, T> Col<E, T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _p: PhantomData,
+        }
+    }
+    /// 列名
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// `IS NULL`
+    pub fn is_null(&self) -> Expr<E> {
+        Expr::new(Node::IsNull(self.name, false))
+    }
+    /// `IS NOT NULL`
+    pub fn is_not_null(&self) -> Expr<E> {
+        Expr::new(Node::IsNull(self.name, true))
+    }
+}
+
+impl<E, T> Col<E, T>
+where
+    T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Send + Sync + 'static,
+{
+    fn cmp(&self, op: &'static str, v: T) -> Expr<E> {
+        Expr::new(Node::Cmp(self.name, op, Arc::new(v)))
+    }
+    /// `=`
+    pub fn eq(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp("=", v.into())
+    }
+    /// `<>`
+    pub fn ne(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp("<>", v.into())
+    }
+    /// `>`
+    pub fn gt(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp(">", v.into())
+    }
+    /// `>=`
+    pub fn ge(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp(">=", v.into())
+    }
+    /// `<`
+    pub fn lt(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp("<", v.into())
+    }
+    /// `<=`
+    pub fn le(&self, v: impl Into<T>) -> Expr<E> {
+        self.cmp("<=", v.into())
+    }
+}
+
+impl<E, T> Col<E, T>
+where
+    T: sqlx::postgres::PgHasArrayType + Send + Sync + 'static,
+    Vec<T>: for<'q> Encode<'q, Postgres> + Type<Postgres>,
+{
+    /// `= ANY(...)`
+    pub fn is_in(&self, v: impl IntoIterator<Item = T>) -> Expr<E> {
+        Expr::new(Node::Any(
+            self.name,
+            Arc::new(v.into_iter().collect::<Vec<_>>()),
+        ))
+    }
+}
+
+impl<E> Col<E, String> {
+    /// `LIKE`
+    pub fn like(&self, pattern: impl Into<String>) -> Expr<E> {
+        self.cmp("LIKE", pattern.into())
+    }
+    /// `ILIKE`
+    pub fn ilike(&self, pattern: impl Into<String>) -> Expr<E> {
+        self.cmp("ILIKE", pattern.into())
+    }
+    /// `ILIKE '%v%'`
+    pub fn contains(&self, v: impl std::fmt::Display) -> Expr<E> {
+        self.cmp("ILIKE", format!("%{}%", v))
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    Cmp(&'static str, &'static str, Arc<dyn Value>),
+    IsNull(&'static str, bool),
+    Any(&'static str, Arc<dyn Value>),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+}
+
+/// 实体 `E` 的查询条件，可通过 `and`、`or` 及 `!` 组合
+pub struct Expr<E> {
+    node: Node,
+    _e: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for Expr<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.node.clone())
+    }
+}
+
+impl<E> std::fmt::Debug for Expr<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut q = QueryBuilder::new("");
+        self.push_to(&mut q);
+        f.debug_tuple("Expr").field(&q.sql()).finish()
+    }
+}
+
+impl<E> std::ops::Not for Expr<E> {
+    type Output = Self;
+
+    /// `NOT (...)`
+    fn not(self) -> Self {
+        let node = match self.node {
+            Node::Not(n) => *n,
+            n => Node::Not(Box::new(n)),
+        };
+        Self::new(node)
+    }
+}
+
+impl<E> Expr<E> {
+    fn new(node: Node) -> Self {
+        Self {
+            node,
+            _e: PhantomData,
+        }
+    }
+    /// `AND`
+    pub fn and(self, other: Expr<E>) -> Self {
+        let node = match self.node {
+            Node::And(mut v) => {
+                v.push(other.node);
+                Node::And(v)
+            }
+            n => Node::And(vec![n, other.node]),
+        };
+        Self::new(node)
+    }
+    /// `OR`
+    pub fn or(self, other: Expr<E>) -> Self {
+        let node = match self.node {
+            Node::Or(mut v) => {
+                v.push(other.node);
+                Node::Or(v)
+            }
+            n => Node::Or(vec![n, other.node]),
+        };
+        Self::new(node)
+    }
+    /// 将条件追加到查询，组合条件会加上括号，参数编号由 `QueryBuilder` 维护
+    pub fn push_to<'q>(&'q self, q: &mut QueryBuilder<'q, Postgres>) {
+        self.node.push_to(q);
+    }
+}
+
+impl Node {
+    fn push_to<'q>(&'q self, q: &mut QueryBuilder<'q, Postgres>) {
+        match self {
+            Self::Cmp(col, op, v) => {
+                q.push(format!("{:?} {} ", col, op));
+                v.push_bind(q);
+            }
+            Self::IsNull(col, not) => {
+                q.push(format!(
+                    "{:?} IS {}NULL",
+                    col,
+                    if *not { "NOT " } else { "" }
+                ));
+            }
+            Self::Any(col, v) => {
+                q.push(format!("{:?} = ANY(", col));
+                v.push_bind(q);
+                q.push(")");
+            }
+            Self::And(v) => Self::push_list(q, v, " AND "),
+            Self::Or(v) => Self::push_list(q, v, " OR "),
+            Self::Not(n) => match n.as_ref() {
+                Self::And(_) | Self::Or(_) => {
+                    q.push("NOT ");
+                    n.push_to(q);
+                }
+                n => {
+                    q.push("NOT (");
+                    n.push_to(q);
+                    q.push(")");
+                }
+            },
+        }
+    }
+    fn push_list<'q>(q: &mut QueryBuilder<'q, Postgres>, v: &'q [Node], sep: &str) {
+        q.push("(");
+        for (idx, n) in v.iter().enumerate() {
+            if idx > 0 {
+                q.push(sep);
+            }
+            n.push_to(q);
+        }
+        q.push(")");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct M;
+
+    const A: Col<M, i32> = Col::new("a");
+    const B: Col<M, i32> = Col::new("b");
+    const C: Col<M, String> = Col::new("c");
+
+    fn sql(e: &Expr<M>) -> String {
+        let mut q = QueryBuilder::new("");
+        e.push_to(&mut q);
+        q.sql().to_string()
+    }
+
+    #[test]
+    fn or_then_and() {
+        let e = A.eq(1).or(B.gt(2)).and(C.eq("x"));
+        assert_eq!(sql(&e), r#"(("a" = $1 OR "b" > $2) AND "c" = $3)"#);
+    }
+
+    #[test]
+    fn and_flattens() {
+        let e = A.eq(1).and(B.eq(2)).and(C.is_null());
+        assert_eq!(sql(&e), r#"("a" = $1 AND "b" = $2 AND "c" IS NULL)"#);
+    }
+
+    #[test]
+    fn not_group() {
+        let e = !(A.eq(1).and(B.eq(2)));
+        assert_eq!(sql(&e), r#"NOT ("a" = $1 AND "b" = $2)"#);
+        let e = (!A.eq(1)).or(!B.is_in([2, 3]));
+        assert_eq!(sql(&e), r#"(NOT ("a" = $1) OR NOT ("b" = ANY($2)))"#);
+    }
+
+    #[test]
+    fn double_not() {
+        let e = !!A.eq(1);
+        assert_eq!(sql(&e), r#""a" = $1"#);
+    }
+
+    #[test]
+    fn after_list_filter() {
+        let e = A.ge(1).or(C.contains("axum"));
+        let mut q = QueryBuilder::new("SELECT * FROM \"m\" WHERE 1=1");
+        q.push(" AND \"b\" = ").push_bind(7);
+        q.push(" AND ");
+        e.push_to(&mut q);
+        q.push(" LIMIT ").push_bind(10i64);
+        assert_eq!(
+            q.sql(),
+            r#"SELECT * FROM "m" WHERE 1=1 AND "b" = $1 AND ("a" >= $2 OR "c" ILIKE $3) LIMIT $4"#
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::{Error, ValidationErrors};
+
+/// 错误响应的 JSON 结构
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: u16,
+    pub msg: String,
+    /// 字段校验错误，仅在校验失败时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<ValidationErrors>,
+}
+
+impl Error {
+    /// 错误对应的 HTTP 状态码
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::StaleObject | Self::UniqueViolation { .. } | Self::ForeignKeyViolation { .. } => {
+                StatusCode::CONFLICT
+            }
+            Self::CheckViolation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::EmptyFilter | Self::Aborted(_) => StatusCode::BAD_REQUEST,
+            Self::Sqlx(_) | Self::TooManyRows(_) | Self::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let msg = match &self {
+            // 内部错误的详情可能包含表结构和 SQL，只记录日志，不返回给客户端
+            Self::Sqlx(_) | Self::TooManyRows(_) | Self::Io(_) => {
+                tracing::error!("{}", self);
+                "服务器内部错误".to_string()
+            }
+            _ => self.to_string(),
+        };
+        let body = ErrorBody {
+            code: status.as_u16(),
+            msg,
+            errors: match self {
+                Self::Validation(e) => Some(e),
+                _ => None,
+            },
+        };
+        (status, Json(body)).into_response()
+    }
+}
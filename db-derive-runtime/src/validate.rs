@@ -0,0 +1,126 @@
+//! `#[db(validate(...))]` 使用的校验规则
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// 字段校验错误：字段名 → 错误信息
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<&'static str, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一条字段错误
+    pub fn add(&mut self, field: &'static str, msg: impl Into<String>) {
+        self.0.entry(field).or_default().push(msg.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 全部字段错误
+    pub fn errors(&self) -> &BTreeMap<&'static str, Vec<String>> {
+        &self.0
+    }
+
+    /// 没有错误时返回 `Ok(())`
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("字段校验失败")?;
+        for (idx, (field, msgs)) in self.0.iter().enumerate() {
+            let sep = if idx == 0 { "：" } else { "；" };
+            write!(f, "{}{} {}", sep, field, msgs.join("，"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// 字符数在 `[min, max]` 之内
+pub fn len(v: impl AsRef<str>, min: Option<usize>, max: Option<usize>) -> Option<String> {
+    let n = v.as_ref().chars().count();
+    match (min, max) {
+        (Some(min), _) if n < min => Some(format!("长度不能少于{}个字符", min)),
+        (_, Some(max)) if n > max => Some(format!("长度不能超过{}个字符", max)),
+        _ => None,
+    }
+}
+
+/// 邮箱格式
+pub fn email(v: impl AsRef<str>) -> Option<String> {
+    let v = v.as_ref();
+    let ok = match v.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !v.contains(char::is_whitespace)
+        }
+        None => false,
+    };
+    (!ok).then(|| "邮箱格式不正确".to_string())
+}
+
+/// 取值在 `[min, max]` 之内
+pub fn range<T: PartialOrd + Display>(v: &T, min: Option<T>, max: Option<T>) -> Option<String> {
+    match (min, max) {
+        (Some(min), _) if *v < min => Some(format!("不能小于{}", min)),
+        (_, Some(max)) if *v > max => Some(format!("不能大于{}", max)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn len_bounds() {
+        assert_eq!(len("a", Some(1), Some(3)), None);
+        assert_eq!(len("abc", Some(1), Some(3)), None);
+        assert!(len("", Some(1), Some(3)).is_some());
+        assert!(len("abcd", Some(1), Some(3)).is_some());
+        // 按字符计数
+        assert_eq!(len("中文字", None, Some(3)), None);
+        assert_eq!(len("", None, None), None);
+    }
+
+    #[test]
+    fn email_format() {
+        assert_eq!(email("team@axum.rs"), None);
+        assert!(email("a@b").is_some());
+        assert!(email("a@.com").is_some());
+        assert!(email("a@com.").is_some());
+        assert!(email("@axum.rs").is_some());
+        assert!(email("a@b@axum.rs").is_some());
+        assert!(email("team @axum.rs").is_some());
+        assert!(email("team@axum.rs ").is_some());
+        assert!(email("axum.rs").is_some());
+    }
+
+    #[test]
+    fn range_bounds() {
+        assert_eq!(range(&0.0, Some(0.0), None), None);
+        assert_eq!(range(&100, Some(0), Some(100)), None);
+        assert_eq!(range(&-0.5, Some(0.0), None).as_deref(), Some("不能小于0"));
+        assert_eq!(range(&101, None, Some(100)).as_deref(), Some("不能大于100"));
+    }
+}
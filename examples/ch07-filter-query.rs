@@ -0,0 +1,32 @@
+use db_derive::Db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Db, Default, Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[db(table = "messages", pk = "id")]
+#[db(filter_derive(Deserialize, Default, Clone))]
+pub struct Message {
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+
+    #[db(list_opt)]
+    #[db(skip_update)]
+    pub user_id: String,
+
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub content: String,
+
+    #[db(skip_update)]
+    pub dateline: chrono::DateTime<chrono::Local>,
+}
+
+fn main() {
+    // 与 `axum::extract::Query<MessageListFilter>` 的解析方式一致
+    let f: MessageListFilter =
+        serde_urlencoded::from_str("page=2&page_size=20&user_id=AXUM-RS&content=hello").unwrap();
+    println!("{:?}", f);
+
+    let f: MessageListFilter = serde_urlencoded::from_str("content=hello").unwrap();
+    println!("{:?}", f.clone());
+}
@@ -0,0 +1,41 @@
+use db_derive::Db;
+use serde::{Deserialize, Serialize};
+use utoipa::OpenApi;
+
+#[derive(Db, Default, Debug, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+#[db(table = "messages", pk = "id", utoipa)]
+#[db(filter_derive(Deserialize))]
+pub struct Message {
+    /// 消息ID
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+
+    /// 发送者ID
+    #[db(list_opt)]
+    #[db(skip_update)]
+    pub user_id: String,
+
+    /// 消息内容，模糊匹配
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub content: String,
+
+    #[db(skip_update)]
+    pub dateline: chrono::DateTime<chrono::Local>,
+}
+
+#[derive(OpenApi)]
+#[openapi(components(schemas(
+    Message,
+    MessageListFilter,
+    MessageFindBy,
+    MessageFindFilter,
+    MessagePaginateReq,
+    MessagePaginate
+)))]
+struct ApiDoc;
+
+fn main() {
+    println!("{}", ApiDoc::openapi().to_pretty_json().unwrap());
+}
@@ -0,0 +1,52 @@
+use db_derive::Db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Db, Default, Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[db(table = "messages", pk = "id", ddl)]
+pub struct Message {
+    #[db(find)]
+    #[db(skip_insert)]
+    pub id: i32,
+
+    #[db(list_opt)]
+    #[db(skip_update)]
+    #[db(sql_type = "CHAR(20)")]
+    pub user_id: String,
+
+    #[db(list_opt)]
+    #[db(opt_like)]
+    pub content: String,
+
+    #[db(unique)]
+    pub slug: Option<String>,
+
+    #[db(index = "idx_messages_category_tag")]
+    pub category: i32,
+
+    #[db(index = "idx_messages_category_tag")]
+    pub tag: i32,
+
+    #[db(version)]
+    #[db(default = "0")]
+    pub version: i64,
+
+    #[db(skip_update)]
+    #[db(default = "CURRENT_TIMESTAMP")]
+    pub dateline: chrono::DateTime<chrono::Local>,
+}
+
+fn main() {
+    println!(
+        "SELECT {} FROM {:?} WHERE {:?} = $1",
+        Message::COLUMN_LIST,
+        Message::TABLE,
+        Message::PK
+    );
+    println!("{:?}", Message::INSERT_COLUMNS);
+    println!("{:?}", Message::UPDATE_COLUMNS);
+    println!("{};", Message::create_table_sql());
+    for sql in Message::index_sql() {
+        println!("{};", sql);
+    }
+    println!("{};", Message::drop_table_sql());
+}